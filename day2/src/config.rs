@@ -0,0 +1,88 @@
+// scoring rules loaded from a toml file
+//
+// example :
+//   [shapes]
+//   rock = 1
+//   paper = 2
+//   scissor = 3
+//
+//   [outcomes]
+//   loss = 0
+//   draw = 3
+//   win = 6
+//
+//   [streak]
+//   length = 3
+//   bonus = 5
+//
+// every section is optional, missing values keep the standard scoring
+
+use crate::game::{Rules, Scoring, StreakBonus};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScoringConfig {
+    #[serde(default)]
+    shapes: HashMap<String, i32>,
+    outcomes: Option<OutcomesConfig>,
+    streak: Option<StreakConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OutcomesConfig {
+    loss: Option<i32>,
+    draw: Option<i32>,
+    win: Option<i32>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StreakConfig {
+    length: usize,
+    bonus: i32,
+}
+
+// parse scoring rules : String -> Scoring
+// shape names are matched against the rules (case insensitive)
+pub fn parse_scoring(content: &str, rules: &Rules) -> Scoring {
+    let config: ScoringConfig = match toml::from_str(content) {
+        Ok(config) => config,
+        Err(e) => panic!("Invalid scoring config : {}", e),
+    };
+    let mut scoring = Scoring::standard(rules);
+
+    for (name, value) in config.shapes {
+        match rules.shape(&name) {
+            Some(shape) => scoring.shape_values[shape] = value,
+            None => panic!("Unknown shape in scoring config : {}", name),
+        }
+    }
+
+    if let Some(outcomes) = config.outcomes {
+        scoring.loss = outcomes.loss.unwrap_or(scoring.loss);
+        scoring.draw = outcomes.draw.unwrap_or(scoring.draw);
+        scoring.win = outcomes.win.unwrap_or(scoring.win);
+    }
+
+    if let Some(streak) = config.streak {
+        if streak.length == 0 {
+            panic!("Invalid streak length : 0");
+        }
+        scoring.streak = Some(StreakBonus {
+            length: streak.length,
+            bonus: streak.bonus,
+        });
+    }
+
+    scoring
+}
+
+// read a scoring config file
+pub fn load_scoring(path: &str, rules: &Rules) -> Scoring {
+    let content = fs::read_to_string(path).unwrap();
+    parse_scoring(&content, rules)
+}
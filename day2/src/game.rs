@@ -0,0 +1,157 @@
+// generic cyclic dominance game engine
+//
+// shapes are identified by their index in the rules, and every round is
+// resolved with a lookup in an outcome table derived from the rules
+
+pub type Shape = usize;
+
+// outcome of a round, seen from the point of view of the first player
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Outcome {
+    FirstWin,
+    Draw,
+    SecondWin,
+}
+
+// rules of a game : a list of shape names and the outcome of every pair of shapes
+// outcomes[first][second] is the outcome when first plays against second
+pub struct Rules {
+    names: Vec<String>,
+    outcomes: Vec<Vec<Outcome>>,
+}
+
+impl Rules {
+    // build the rules of a cyclic dominance game
+    // there must be an odd number of shapes (at least 3), and each shape beats
+    // the (n - 1) / 2 shapes that precede it in the cycle
+    pub fn cyclic(names: &[&str]) -> Rules {
+        let n = names.len();
+        if n < 3 || n.is_multiple_of(2) {
+            panic!("Cyclic game needs an odd number of shapes, got {}", n);
+        }
+
+        let outcomes = (0..n)
+            .map(|first| {
+                (0..n)
+                    .map(|second| match (first + n - second) % n {
+                        0 => Outcome::Draw,
+                        d if d <= (n - 1) / 2 => Outcome::FirstWin,
+                        _ => Outcome::SecondWin,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Rules {
+            names: names.iter().map(|s| s.to_string()).collect(),
+            outcomes,
+        }
+    }
+
+    // Rock (0), Paper (1), Scissor (2)
+    pub fn rock_paper_scissors() -> Rules {
+        Rules::cyclic(&["Rock", "Paper", "Scissor"])
+    }
+
+    // Rock (0), Spock (1), Paper (2), Lizard (3), Scissor (4)
+    pub fn rock_paper_scissors_lizard_spock() -> Rules {
+        Rules::cyclic(&["Rock", "Spock", "Paper", "Lizard", "Scissor"])
+    }
+
+    // number of shapes in the game
+    pub fn shape_count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, shape: Shape) -> &str {
+        &self.names[shape]
+    }
+
+    // find a shape from its name (case insensitive)
+    pub fn shape(&self, name: &str) -> Option<Shape> {
+        self.names.iter().position(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    pub fn simulate(&self, first: Shape, second: Shape) -> Outcome {
+        self.outcomes[first][second]
+    }
+
+    // find what the second player has to play to achieve the expected outcome
+    // when several shapes are possible, the first one in cycle order starting from `first` is chosen
+    pub fn find_shape(&self, first: Shape, outcome: Outcome) -> Shape {
+        let n = self.shape_count();
+        (0..n)
+            .map(|k| (first + k) % n)
+            .find(|second| self.simulate(first, *second) == outcome)
+            .unwrap()
+    }
+}
+
+// bonus given to the second player for each win once it has won `length` rounds in a row
+pub struct StreakBonus {
+    pub length: usize,
+    pub bonus: i32,
+}
+
+// scoring of a round, from the point of view of the second player
+pub struct Scoring {
+    pub shape_values: Vec<i32>,
+    pub loss: i32,
+    pub draw: i32,
+    pub win: i32,
+    pub streak: Option<StreakBonus>,
+}
+
+impl Scoring {
+    // shapes are worth 1, 2, 3, ... in rules order, and a round is worth 0 (loss), 3 (draw) or 6 (win)
+    pub fn standard(rules: &Rules) -> Scoring {
+        Scoring {
+            shape_values: (1..=rules.shape_count() as i32).collect(),
+            loss: 0,
+            draw: 3,
+            win: 6,
+            streak: None,
+        }
+    }
+
+    pub fn shape_score(&self, shape: Shape) -> i32 {
+        self.shape_values[shape]
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> i32 {
+        match outcome {
+            Outcome::FirstWin => self.loss,
+            Outcome::Draw => self.draw,
+            Outcome::SecondWin => self.win,
+        }
+    }
+
+    // score of the second player for a round
+    pub fn round_score(&self, rules: &Rules, first: Shape, second: Shape) -> i32 {
+        self.shape_score(second) + self.outcome_score(rules.simulate(first, second))
+    }
+
+    // score of the second player for each round of a game, streak bonuses included
+    pub fn round_scores(&self, rules: &Rules, rounds: impl Iterator<Item = (Shape, Shape)>) -> Vec<i32> {
+        let mut wins_in_a_row = 0;
+
+        rounds
+            .map(|(first, second)| {
+                let outcome = rules.simulate(first, second);
+                wins_in_a_row = if outcome == Outcome::SecondWin { wins_in_a_row + 1 } else { 0 };
+
+                let bonus = match &self.streak {
+                    Some(streak) if wins_in_a_row >= streak.length => streak.bonus,
+                    _ => 0,
+                };
+
+                self.shape_score(second) + self.outcome_score(outcome) + bonus
+            })
+            .collect()
+    }
+
+    // score of the second player for a whole game, streak bonuses included
+    pub fn game_score(&self, rules: &Rules, rounds: impl Iterator<Item = (Shape, Shape)>) -> i32 {
+        self.round_scores(rules, rounds).iter().sum()
+    }
+}
@@ -0,0 +1,109 @@
+// enumerate every interpretation of the X/Y/Z column of the strategy guide
+// and score each of them, to see how sensitive the guide is to a misunderstanding
+
+use crate::game::Scoring;
+use crate::{solve_part1, solve_part2, Hand, InputPart1, InputPart2, Result};
+
+// strategy guide with the second column left uninterpreted : (first hand, column index)
+// X is column 0, Y is column 1 and Z is column 2
+type Guide = Vec<(Hand, usize)>;
+
+// an interpretation maps each column index to a value
+type Mapping<T> = [T; 3];
+
+const HANDS: Mapping<Hand> = [Hand::Rock, Hand::Paper, Hand::Scissor];
+const RESULTS: Mapping<Result> = [Result::FirstWin, Result::Draw, Result::SecondWin];
+const COLUMNS: [&str; 3] = ["X", "Y", "Z"];
+
+// parse the input : String -> Guide
+// format : same as parse_input_part1, but the second column is kept as X, Y or Z
+fn parse_guide(input: &str) -> Guide {
+    input
+        .lines()
+        .map(|line| {
+            let w: Vec<&str> = line.split(' ').collect();
+            let hand = match w[0] {
+                "A" => Hand::Rock,
+                "B" => Hand::Paper,
+                "C" => Hand::Scissor,
+                x => panic!("Invalid hand : {}", x),
+            };
+            let column = match COLUMNS.iter().position(|c| *c == w[1]) {
+                Some(column) => column,
+                None => panic!("Invalid column : {}", w[1]),
+            };
+            (hand, column)
+        })
+        .collect()
+}
+
+// all 27 mappings of the 3 columns, including the ones giving the same value to several columns (X=Y=Rock)
+fn mappings<T: Copy>(values: &Mapping<T>) -> Vec<Mapping<T>> {
+    (0..27)
+        .map(|i| [values[i / 9], values[i / 3 % 3], values[i % 3]])
+        .collect()
+}
+
+// score every mapping of the columns to hands
+fn score_as_hands(guide: &Guide, scoring: &Scoring) -> Vec<(Mapping<Hand>, i32)> {
+    mappings(&HANDS)
+        .into_iter()
+        .map(|mapping| {
+            let input: InputPart1 = guide.iter().map(|(hand, column)| (*hand, mapping[*column])).collect();
+            (mapping, solve_part1(&input, scoring))
+        })
+        .collect()
+}
+
+// score every mapping of the columns to results
+fn score_as_results(guide: &Guide, scoring: &Scoring) -> Vec<(Mapping<Result>, i32)> {
+    mappings(&RESULTS)
+        .into_iter()
+        .map(|mapping| {
+            let input: InputPart2 = guide.iter().map(|(hand, column)| (*hand, mapping[*column])).collect();
+            (mapping, solve_part2(&input, scoring))
+        })
+        .collect()
+}
+
+// print one line per mapping, flagging the guide's own interpretation and the extremes
+fn print_scores<T: std::fmt::Debug + PartialEq>(title: &str, scores: &[(Mapping<T>, i32)], expected: &Mapping<T>) {
+    let max = scores.iter().map(|(_, score)| *score).max().unwrap();
+    let min = scores.iter().map(|(_, score)| *score).min().unwrap();
+
+    println!("{} :", title);
+    for (mapping, score) in scores {
+        let description = COLUMNS
+            .iter()
+            .zip(mapping.iter())
+            .map(|(column, value)| format!("{}={:?}", column, value))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let mut flags = vec![];
+        if mapping == expected {
+            flags.push("guide");
+        }
+        if *score == max {
+            flags.push("max");
+        }
+        if *score == min {
+            flags.push("min");
+        }
+
+        if flags.is_empty() {
+            println!("  {} : {}", description, score);
+        } else {
+            println!("  {} : {} <- {}", description, score, flags.join(", "));
+        }
+    }
+    println!("  spread : {}", max - min);
+}
+
+// print the score of every interpretation of the strategy guide
+pub fn print_interpretations(input: &str, scoring: &Scoring) {
+    let guide = parse_guide(input);
+
+    print_scores("X/Y/Z as hands", &score_as_hands(&guide, scoring), &HANDS);
+    print_scores("X/Y/Z as results", &score_as_results(&guide, scoring), &RESULTS);
+}
@@ -1,14 +1,23 @@
+mod config;
+mod game;
+mod interpret;
+mod markov;
+mod tournament;
+
+use game::{Outcome, Rules, Scoring, Shape};
+use std::env;
 use std::fs::File;
 use std::io::Read;
+use std::sync::OnceLock;
 
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum Hand {
     Rock,
     Paper,
     Scissor,
 }
 
-#[derive(Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 enum Result {
     FirstWin,
     Draw,
@@ -80,82 +89,78 @@ fn parse_input_part2(input: &str) -> InputPart2 {
     result
 }
 
-fn simulate(first_hand: &Hand, second_hand: &Hand) -> Result {
-    match (first_hand, second_hand) {
-        (Hand::Rock, Hand::Rock) => Result::Draw,
-        (Hand::Rock, Hand::Paper) => Result::SecondWin,
-        (Hand::Rock, Hand::Scissor) => Result::FirstWin,
-        (Hand::Paper, Hand::Rock) => Result::FirstWin,
-        (Hand::Paper, Hand::Paper) => Result::Draw,
-        (Hand::Paper, Hand::Scissor) => Result::SecondWin,
-        (Hand::Scissor, Hand::Rock) => Result::SecondWin,
-        (Hand::Scissor, Hand::Paper) => Result::FirstWin,
-        (Hand::Scissor, Hand::Scissor) => Result::Draw,
-    }
+// rules of the game, shared by every round
+fn rules() -> &'static Rules {
+    static RULES: OnceLock<Rules> = OnceLock::new();
+    RULES.get_or_init(Rules::rock_paper_scissors)
 }
 
-// shape score :
-//  1 for rock
-//  2 for paper
-//  3 for scissor
-fn shape_score(hand: &Hand) -> i32 {
-    match hand {
-        Hand::Rock => 1,
-        Hand::Paper => 2,
-        Hand::Scissor => 3,
+impl Hand {
+    // index of the hand in the rock paper scissors rules
+    fn shape(&self) -> Shape {
+        match self {
+            Hand::Rock => 0,
+            Hand::Paper => 1,
+            Hand::Scissor => 2,
+        }
+    }
+
+    fn from_shape(shape: Shape) -> Hand {
+        match shape {
+            0 => Hand::Rock,
+            1 => Hand::Paper,
+            2 => Hand::Scissor,
+            x => panic!("Invalid shape : {}", x),
+        }
     }
 }
 
-// result score :
-//  0 for first player win
-//  3 for draw
-//  6 for second player win
-fn result_score(result: &Result) -> i32 {
-    match result {
-        Result::FirstWin => 0,
-        Result::Draw => 3,
-        Result::SecondWin => 6,
+impl Result {
+    fn outcome(&self) -> Outcome {
+        match self {
+            Result::FirstWin => Outcome::FirstWin,
+            Result::Draw => Outcome::Draw,
+            Result::SecondWin => Outcome::SecondWin,
+        }
+    }
+
+    fn from_outcome(outcome: Outcome) -> Result {
+        match outcome {
+            Outcome::FirstWin => Result::FirstWin,
+            Outcome::Draw => Result::Draw,
+            Outcome::SecondWin => Result::SecondWin,
+        }
     }
 }
 
+fn simulate(first_hand: &Hand, second_hand: &Hand) -> Result {
+    Result::from_outcome(rules().simulate(first_hand.shape(), second_hand.shape()))
+}
+
 // solve the problem : Input -> Output
 // compute sum of scores for each round
-fn solve_part1(input: &InputPart1) -> Output {
-    input
+fn solve_part1(input: &InputPart1, scoring: &Scoring) -> Output {
+    let rounds = input
         .iter()
-        .map(|(first_hand, second_hand)| {
-            let result = simulate(first_hand, second_hand);
-            shape_score(second_hand) + result_score(&result)
-        })
-        .sum()
+        .map(|(first_hand, second_hand)| (first_hand.shape(), second_hand.shape()));
+
+    scoring.game_score(rules(), rounds)
 }
 
 // find what to play to achieve expected result
 fn find_hand(first_hand: &Hand, result: &Result) -> Hand {
-    match (first_hand, result) {
-        (Hand::Rock, Result::FirstWin) => Hand::Scissor,
-        (Hand::Rock, Result::Draw) => Hand::Rock,
-        (Hand::Rock, Result::SecondWin) => Hand::Paper,
-        (Hand::Paper, Result::FirstWin) => Hand::Rock,
-        (Hand::Paper, Result::Draw) => Hand::Paper,
-        (Hand::Paper, Result::SecondWin) => Hand::Scissor,
-        (Hand::Scissor, Result::FirstWin) => Hand::Paper,
-        (Hand::Scissor, Result::Draw) => Hand::Scissor,
-        (Hand::Scissor, Result::SecondWin) => Hand::Rock,
-    }
+    Hand::from_shape(rules().find_shape(first_hand.shape(), result.outcome()))
 }
 
 // solve the problem : Input -> Output
 // find what to play to achieve expected result and then compute sum of scores for each round
-fn solve_part2(input: &InputPart2) -> Output {
-    input
-        .iter()
-        .map(|(first_hand, result)| {
-            let second_hand = find_hand(first_hand, result);
-            let result = simulate(first_hand, &second_hand);
-            shape_score(&second_hand) + result_score(&result)
-        })
-        .sum()
+fn solve_part2(input: &InputPart2, scoring: &Scoring) -> Output {
+    let rounds = input.iter().map(|(first_hand, result)| {
+        let second_hand = find_hand(first_hand, result);
+        (first_hand.shape(), second_hand.shape())
+    });
+
+    scoring.game_score(rules(), rounds)
 }
 
 // print the output : Output -> ()
@@ -172,11 +177,81 @@ fn read_input() -> String {
     contents
 }
 
+// print the outcome table of a game : rock-paper-scissors (rps) or rock-paper-scissors-lizard-spock (rpsls)
+fn print_rules(game: &str) {
+    let rules = match game {
+        "rps" => Rules::rock_paper_scissors(),
+        "rpsls" => Rules::rock_paper_scissors_lizard_spock(),
+        x => panic!("Unknown game : {}", x),
+    };
+    let scoring = Scoring::standard(&rules);
+
+    for first in 0..rules.shape_count() {
+        for second in 0..rules.shape_count() {
+            println!(
+                "{} vs {} : {:?} (score {})",
+                rules.name(first),
+                rules.name(second),
+                rules.simulate(first, second),
+                scoring.round_score(&rules, first, second)
+            );
+        }
+    }
+}
+
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // scoring rules can be overridden with --scoring <file>, anywhere on the command line
+    let flag = args.iter().position(|a| a == "--scoring");
+    let scoring_file = flag.map(|i| match args.get(i + 1) {
+        Some(file) => file.as_str(),
+        None => panic!("Missing scoring file after --scoring"),
+    });
+    // the mode and its arguments are what remains once the flag and its file are removed
+    let positional: Vec<&str> = args
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| flag.is_none_or(|f| *i != f && *i != f + 1))
+        .map(|(_, a)| a.as_str())
+        .collect();
+    let mode = positional.get(1).copied();
+
+    if mode == Some("rules") {
+        print_rules(positional.get(2).copied().unwrap_or("rps"));
+        return;
+    }
+
+    let scoring = match scoring_file {
+        Some(file) => config::load_scoring(file, rules()),
+        None => Scoring::standard(rules()),
+    };
+
     let raw_input = read_input();
+    if mode == Some("interpretations") {
+        interpret::print_interpretations(&raw_input, &scoring);
+        return;
+    }
+    if mode == Some("tournament") {
+        // cargo run -- tournament [runs] [rounds per match]
+        let runs = positional.get(2).map(|s| s.parse::<usize>().unwrap()).unwrap_or(100);
+        let rounds = positional.get(3).map(|s| s.parse::<usize>().unwrap()).unwrap_or(1000);
+        let guide = parse_input_part1(&raw_input).into_iter().map(|(_, hand)| hand).collect();
+        tournament::print_tournament(&tournament::default_entrants(guide), runs, rounds, &scoring);
+        return;
+    }
+    if mode == Some("adaptive") {
+        // cargo run -- adaptive [max markov order]
+        let max_order = positional.get(2).map(|s| s.parse::<usize>().unwrap()).unwrap_or(3);
+        let input_part1 = parse_input_part1(&raw_input);
+        let input_part2 = parse_input_part2(&raw_input);
+        markov::print_comparison(&input_part1, &input_part2, max_order, &scoring);
+        return;
+    }
+
     let input_part1 = parse_input_part1(&raw_input);
     let input_part2 = parse_input_part2(&raw_input);
-    let output_part1 = solve_part1(&input_part1);
-    let output_part2 = solve_part2(&input_part2);
+    let output_part1 = solve_part1(&input_part1, &scoring);
+    let output_part2 = solve_part2(&input_part2, &scoring);
     print_output(&output_part1, &output_part2);
 }
@@ -0,0 +1,109 @@
+// adaptive player predicting the opponent's next hand with a markov chain
+//
+// the chain of order k counts, for every sequence of k opponent hands, which
+// hand came next. the prediction backs off to shorter contexts when the
+// current one has never been seen, down to the overall frequency of each hand
+
+use crate::game::Scoring;
+use crate::tournament::Player;
+use crate::{find_hand, rules, solve_part1, solve_part2, Hand, InputPart1, InputPart2, Result};
+use std::collections::HashMap;
+
+const HANDS: [Hand; 3] = [Hand::Rock, Hand::Paper, Hand::Scissor];
+
+pub struct Markov {
+    order: usize,
+    history: Vec<Hand>,
+    // counts[k] maps the last k opponent hands to the count of each following hand
+    counts: Vec<HashMap<Vec<Hand>, [usize; 3]>>,
+}
+
+impl Markov {
+    pub fn new(order: usize) -> Markov {
+        Markov {
+            order,
+            history: vec![],
+            counts: vec![HashMap::new(); order + 1],
+        }
+    }
+
+    // predict the opponent's next hand, using the longest context seen so far
+    pub fn predict(&self) -> Option<Hand> {
+        (0..=self.order.min(self.history.len()))
+            .rev()
+            .filter_map(|k| self.counts[k].get(&self.history[self.history.len() - k..]))
+            .map(|counts| {
+                // ties are broken in Rock, Paper, Scissor order
+                let best = (0..HANDS.len()).rev().max_by_key(|i| counts[*i]).unwrap();
+                HANDS[best]
+            })
+            .next()
+    }
+}
+
+impl Player for Markov {
+    // play what beats the predicted hand, or Rock when nothing is known yet
+    fn play(&mut self) -> Hand {
+        match self.predict() {
+            Some(predicted) => find_hand(&predicted, &Result::SecondWin),
+            None => Hand::Rock,
+        }
+    }
+
+    fn observe(&mut self, opponent: Hand) {
+        for k in 0..=self.order.min(self.history.len()) {
+            let context = self.history[self.history.len() - k..].to_vec();
+            self.counts[k].entry(context).or_insert([0; 3])[opponent.shape()] += 1;
+        }
+        self.history.push(opponent);
+    }
+}
+
+// play the adaptive player against the opponent column of the guide
+// returns the cumulative score after each round, streak bonuses included
+fn play_against(opponent: &[Hand], order: usize, scoring: &Scoring) -> Vec<i32> {
+    let mut player = Markov::new(order);
+    let rounds: Vec<(Hand, Hand)> = opponent
+        .iter()
+        .map(|opponent_hand| {
+            let hand = player.play();
+            player.observe(*opponent_hand);
+            (*opponent_hand, hand)
+        })
+        .collect();
+
+    let mut total = 0;
+    scoring
+        .round_scores(rules(), rounds.iter().map(|(opponent_hand, hand)| (opponent_hand.shape(), hand.shape())))
+        .into_iter()
+        .map(|score| {
+            total += score;
+            total
+        })
+        .collect()
+}
+
+// compare the adaptive players of order 0 to max_order against the strategy guide
+pub fn print_comparison(input_part1: &InputPart1, input_part2: &InputPart2, max_order: usize, scoring: &Scoring) {
+    let opponent: Vec<Hand> = input_part1.iter().map(|(hand, _)| *hand).collect();
+    let rounds = opponent.len();
+
+    println!("guide part1 : {}", solve_part1(input_part1, scoring));
+    println!("guide part2 : {}", solve_part2(input_part2, scoring));
+    if rounds == 0 {
+        println!("no rounds in the strategy guide, nothing to compare");
+        return;
+    }
+
+    // cumulative score at each tenth of the game
+    let checkpoints: Vec<usize> = (1..=10).map(|i| (rounds * i / 10).max(1) - 1).collect();
+    for order in 0..=max_order {
+        let scores = play_against(&opponent, order, scoring);
+        let progress = checkpoints
+            .iter()
+            .map(|i| scores[*i].to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        println!("markov order {} : {} (cumulative : {})", order, scores[rounds - 1], progress);
+    }
+}
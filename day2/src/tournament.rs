@@ -0,0 +1,276 @@
+// round-robin tournament between pluggable player strategies
+//
+// every pair of players plays a match of a fixed number of rounds, and the
+// whole tournament is repeated over many seeded runs to get confidence intervals
+
+use crate::game::Scoring;
+use crate::markov::Markov;
+use crate::{find_hand, rules, simulate, Hand, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const HANDS: [Hand; 3] = [Hand::Rock, Hand::Paper, Hand::Scissor];
+
+// a player of a single match
+pub trait Player {
+    // choose the hand for the next round
+    fn play(&mut self) -> Hand;
+
+    // learn what the opponent played once the round is over
+    fn observe(&mut self, opponent: Hand);
+}
+
+// a strategy entered in the tournament : builds a fresh player for each match from a seed
+pub struct Entrant {
+    pub name: String,
+    pub make: Box<dyn Fn(u64) -> Box<dyn Player>>,
+}
+
+impl Entrant {
+    pub fn new(name: &str, make: impl Fn(u64) -> Box<dyn Player> + 'static) -> Entrant {
+        Entrant {
+            name: name.to_string(),
+            make: Box::new(make),
+        }
+    }
+}
+
+// always plays the same hand
+pub struct Fixed(pub Hand);
+
+impl Player for Fixed {
+    fn play(&mut self) -> Hand {
+        self.0
+    }
+
+    fn observe(&mut self, _opponent: Hand) {}
+}
+
+// plays a uniformly random hand
+pub struct Random(pub StdRng);
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Player for Random {
+    fn play(&mut self) -> Hand {
+        HANDS[self.0.gen_range(0..HANDS.len())]
+    }
+
+    fn observe(&mut self, _opponent: Hand) {}
+}
+
+// counts the opponent's hands and plays what beats the most frequent one
+#[derive(Default)]
+pub struct FrequencyCounter {
+    counts: [usize; 3],
+}
+
+impl Player for FrequencyCounter {
+    fn play(&mut self) -> Hand {
+        // ties are broken in Rock, Paper, Scissor order
+        let predicted = (0..HANDS.len()).rev().max_by_key(|i| self.counts[*i]).unwrap();
+        find_hand(&HANDS[predicted], &Result::SecondWin)
+    }
+
+    fn observe(&mut self, opponent: Hand) {
+        self.counts[opponent.shape()] += 1;
+    }
+}
+
+// plays the hands of the strategy guide in order, starting over once exhausted
+pub struct Guide {
+    hands: Vec<Hand>,
+    next: usize,
+}
+
+impl Guide {
+    // the guide is replayed in a loop, so it needs at least one hand
+    pub fn new(hands: Vec<Hand>) -> Guide {
+        if hands.is_empty() {
+            panic!("Empty strategy guide");
+        }
+        Guide { hands, next: 0 }
+    }
+}
+
+impl Player for Guide {
+    fn play(&mut self) -> Hand {
+        let hand = self.hands[self.next];
+        self.next = (self.next + 1) % self.hands.len();
+        hand
+    }
+
+    fn observe(&mut self, _opponent: Hand) {}
+}
+
+// result of a single match, from the point of view of each player
+struct MatchResult {
+    points: (i32, i32),
+    rounds_won: (usize, usize),
+}
+
+fn play_match(first: &mut dyn Player, second: &mut dyn Player, rounds: usize, scoring: &Scoring) -> MatchResult {
+    let mut hands = vec![];
+    let mut rounds_won = (0, 0);
+
+    for _ in 0..rounds {
+        let first_hand = first.play();
+        let second_hand = second.play();
+        first.observe(second_hand);
+        second.observe(first_hand);
+
+        match simulate(&first_hand, &second_hand) {
+            Result::FirstWin => rounds_won.0 += 1,
+            Result::SecondWin => rounds_won.1 += 1,
+            Result::Draw => (),
+        }
+        hands.push((first_hand.shape(), second_hand.shape()));
+    }
+
+    let points = (
+        scoring.game_score(rules(), hands.iter().map(|(a, b)| (*b, *a))),
+        scoring.game_score(rules(), hands.iter().copied()),
+    );
+
+    MatchResult { points, rounds_won }
+}
+
+// standing of a player in a single run
+#[derive(Clone, Default)]
+struct Standing {
+    points: i32,
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+
+// results of a single run : standings, and points[i][j] scored by player i against player j
+struct Run {
+    standings: Vec<Standing>,
+    points: Vec<Vec<i32>>,
+}
+
+// mix the run seed and the match number into the seed of a player
+fn player_seed(seed: u64, match_number: usize, side: u64) -> u64 {
+    seed.wrapping_mul(6364136223846793005)
+        .wrapping_add((match_number as u64) << 1 | side)
+        .wrapping_mul(1442695040888963407)
+}
+
+fn run_tournament(entrants: &[Entrant], rounds: usize, scoring: &Scoring, seed: u64) -> Run {
+    let n = entrants.len();
+    let mut standings = vec![Standing::default(); n];
+    let mut points = vec![vec![0; n]; n];
+    let mut match_number = 0;
+
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let mut first = (entrants[i].make)(player_seed(seed, match_number, 0));
+            let mut second = (entrants[j].make)(player_seed(seed, match_number, 1));
+            let result = play_match(first.as_mut(), second.as_mut(), rounds, scoring);
+            match_number += 1;
+
+            points[i][j] = result.points.0;
+            points[j][i] = result.points.1;
+            standings[i].points += result.points.0;
+            standings[j].points += result.points.1;
+
+            // the match goes to the player who won the most rounds
+            if result.rounds_won.0 > result.rounds_won.1 {
+                standings[i].wins += 1;
+                standings[j].losses += 1;
+            } else if result.rounds_won.0 < result.rounds_won.1 {
+                standings[i].losses += 1;
+                standings[j].wins += 1;
+            } else {
+                standings[i].draws += 1;
+                standings[j].draws += 1;
+            }
+        }
+    }
+
+    Run { standings, points }
+}
+
+// mean and half width of the 95% confidence interval
+fn confidence_interval(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (mean, 0.0);
+    }
+    let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / (n - 1.0);
+    (mean, 1.96 * (variance / n).sqrt())
+}
+
+// run the tournament `runs` times and print standings and head to head table
+pub fn print_tournament(entrants: &[Entrant], runs: usize, rounds: usize, scoring: &Scoring) {
+    let n = entrants.len();
+    let results: Vec<Run> = (0..runs)
+        .map(|run| run_tournament(entrants, rounds, scoring, run as u64))
+        .collect();
+    let mean = |f: &dyn Fn(&Run) -> f64| results.iter().map(f).sum::<f64>() / runs as f64;
+
+    let mut standings: Vec<(usize, (f64, f64))> = (0..n)
+        .map(|i| {
+            let points: Vec<f64> = results.iter().map(|r| r.standings[i].points as f64).collect();
+            (i, confidence_interval(&points))
+        })
+        .collect();
+    standings.sort_by(|a, b| b.1 .0.total_cmp(&a.1 .0));
+
+    println!("standings ({} runs, {} rounds per match) :", runs, rounds);
+    for (rank, (i, (points, margin))) in standings.iter().enumerate() {
+        println!(
+            "  {}. {} : {:.1} ± {:.1} points, {:.2} wins, {:.2} draws, {:.2} losses",
+            rank + 1,
+            entrants[*i].name,
+            points,
+            margin,
+            mean(&|r| r.standings[*i].wins as f64),
+            mean(&|r| r.standings[*i].draws as f64),
+            mean(&|r| r.standings[*i].losses as f64),
+        );
+    }
+
+    let width = entrants.iter().map(|e| e.name.len()).max().unwrap().max(9);
+    println!("head to head (mean points of row against column) :");
+    print!("  {:width$}", "", width = width);
+    for entrant in entrants {
+        print!(" {:>width$}", entrant.name, width = width);
+    }
+    println!();
+    for (i, entrant) in entrants.iter().enumerate() {
+        print!("  {:width$}", entrant.name, width = width);
+        for j in 0..n {
+            if i == j {
+                print!(" {:>width$}", "-", width = width);
+            } else {
+                print!(" {:>width$.1}", mean(&|r| r.points[i][j] as f64), width = width);
+            }
+        }
+        println!();
+    }
+}
+
+// the default field : fixed hands, random, frequency counter, markov chains and the strategy guide
+// the guide only enters when it has at least one round
+pub fn default_entrants(guide: Vec<Hand>) -> Vec<Entrant> {
+    let mut entrants = vec![
+        Entrant::new("rock", |_| Box::new(Fixed(Hand::Rock))),
+        Entrant::new("paper", |_| Box::new(Fixed(Hand::Paper))),
+        Entrant::new("scissor", |_| Box::new(Fixed(Hand::Scissor))),
+        Entrant::new("random", |seed| Box::new(Random::new(seed))),
+        Entrant::new("frequency", |_| Box::<FrequencyCounter>::default()),
+        Entrant::new("markov1", |_| Box::new(Markov::new(1))),
+        Entrant::new("markov2", |_| Box::new(Markov::new(2))),
+    ];
+    if !guide.is_empty() {
+        entrants.push(Entrant::new("guide", move |_| Box::new(Guide::new(guide.clone()))));
+    }
+    entrants
+}
@@ -0,0 +1,76 @@
+// audit of the rucksacks : list every item type shared between the two compartments
+// and flag the lines that the solvers would silently mishandle
+
+use crate::item_set::ItemSet;
+use crate::priority::PriorityTable;
+use crate::split_compartments;
+
+// data quality issues found on a line
+#[derive(Debug, Eq, PartialEq)]
+enum Issue {
+    // the line has an odd number of items, so the compartments are not the same size
+    OddLength,
+    // the line contains characters that are not item types
+    InvalidItems(String),
+    // no item type is shared between the compartments (counted as 0 by the solver)
+    NoSharedItem,
+    // several item types are shared between the compartments
+    MultipleSharedItems,
+}
+
+struct LineReport {
+    line: usize,
+    length: usize,
+    shared: String,
+    issues: Vec<Issue>,
+}
+
+fn audit_line(line: usize, l: &str, table: &PriorityTable) -> (LineReport, ItemSet) {
+    let mut issues = vec![];
+    let length = l.chars().count();
+    if !length.is_multiple_of(2) {
+        issues.push(Issue::OddLength);
+    }
+
+    let invalid: String = l.chars().filter(| c | table.index(*c).is_none()).collect();
+    if !invalid.is_empty() {
+        issues.push(Issue::InvalidItems(invalid));
+    }
+
+    // invalid characters are left out so that the rest of the line can still be checked
+    let items: String = l.chars().filter(| c | table.index(*c).is_some()).collect();
+    let (s1, s2) = split_compartments(&items);
+    let (set1, set2) = (ItemSet::from_items(s1, table), ItemSet::from_items(s2, table));
+    let shared = set1.clone().intersection(set2.clone());
+    match shared.len() {
+        0 => issues.push(Issue::NoSharedItem),
+        1 => (),
+        _ => issues.push(Issue::MultipleSharedItems),
+    }
+
+    let report = LineReport { line, length, shared: shared.items(table).collect(), issues };
+    (report, set1.union(set2))
+}
+
+// print the report of every line, then a summary of the issues
+pub fn print_audit(input: &str, table: &PriorityTable) {
+    let (reports, sets): (Vec<LineReport>, Vec<ItemSet>) =
+        input.lines().enumerate().map(| (i, l) | audit_line(i + 1, l, table)).unzip();
+    let seen = sets.into_iter().fold(ItemSet::default(), ItemSet::union);
+
+    for report in &reports {
+        let shared = if report.shared.is_empty() { "-" } else { &report.shared };
+        if report.issues.is_empty() {
+            println!("line {} : length {}, shared {}", report.line, report.length, shared);
+        } else {
+            println!("line {} : length {}, shared {} <- {:?}", report.line, report.length, shared, report.issues);
+        }
+    }
+
+    let count = | f: fn(&Issue) -> bool | reports.iter().filter(| r | r.issues.iter().any(f)).count();
+    println!("{} lines, {} of {} item types seen", reports.len(), seen.len(), table.len());
+    println!("  odd length : {}", count(| i | *i == Issue::OddLength));
+    println!("  invalid items : {}", count(| i | matches!(i, Issue::InvalidItems(_))));
+    println!("  no shared item : {}", count(| i | *i == Issue::NoSharedItem));
+    println!("  multiple shared items : {}", count(| i | *i == Issue::MultipleSharedItems));
+}
@@ -0,0 +1,141 @@
+// badge assignment for unordered elves : find a partition of the rucksacks into
+// groups of size k where every group shares exactly one item type
+//
+// the search is an exhaustive backtracking, so when it fails no such partition exists
+
+use crate::item_set::ItemSet;
+use crate::priority::PriorityTable;
+
+// enumerate every group of group_size rucksacks sharing exactly one item type
+// groups are built in increasing index order, and a partial group is dropped as soon as it shares nothing
+fn valid_groups(sets: &[ItemSet], group_size: usize) -> Vec<Vec<usize>> {
+    fn extend(sets: &[ItemSet], group_size: usize, group: &mut Vec<usize>, common: ItemSet, groups: &mut Vec<Vec<usize>>) {
+        if group.len() == group_size {
+            if common.len() == 1 {
+                groups.push(group.clone());
+            }
+            return;
+        }
+
+        let start = group.last().map(| i | i + 1).unwrap_or(0);
+        for next in start..sets.len() {
+            let next_common = common.clone().intersection(sets[next].clone());
+            // adding more rucksacks can only remove common items
+            if next_common.is_empty() {
+                continue;
+            }
+            group.push(next);
+            extend(sets, group_size, group, next_common, groups);
+            group.pop();
+        }
+    }
+
+    let mut groups = vec![];
+    for first in 0..sets.len() {
+        extend(sets, group_size, &mut vec![first], sets[first].clone(), &mut groups);
+    }
+    groups
+}
+
+// exact cover search over the valid groups : every rucksack must be in exactly one chosen group
+struct Search<'a> {
+    groups: &'a [Vec<usize>],
+    // ids of the groups containing each rucksack
+    containing: Vec<Vec<usize>>,
+    assigned: Vec<bool>,
+    chosen: Vec<usize>,
+    // number of search nodes explored, reported to show the size of the search
+    nodes: usize,
+}
+
+impl Search<'_> {
+    fn is_available(&self, group: usize) -> bool {
+        self.groups[group].iter().all(| r | !self.assigned[*r])
+    }
+
+    // assign every remaining rucksack to a group, returns false if it is not possible
+    fn solve(&mut self) -> bool {
+        self.nodes += 1;
+
+        // the unassigned rucksack with the fewest available groups is the most constrained one
+        let most_constrained = (0..self.assigned.len())
+            .filter(| r | !self.assigned[*r])
+            .map(| r | {
+                let available: Vec<usize> = self.containing[r].iter().copied().filter(| g | self.is_available(*g)).collect();
+                (r, available)
+            })
+            .min_by_key(| (_, available) | available.len());
+
+        let available = match most_constrained {
+            Some((_, available)) => available,
+            None => return true,
+        };
+
+        for group in available {
+            self.groups[group].iter().for_each(| r | self.assigned[*r] = true);
+            self.chosen.push(group);
+            if self.solve() {
+                return true;
+            }
+            self.chosen.pop();
+            self.groups[group].iter().for_each(| r | self.assigned[*r] = false);
+        }
+
+        false
+    }
+}
+
+// find a partition of the rucksacks in groups of group_size sharing exactly one item type
+// returns the groups as indexes in the input, and the number of explored nodes
+fn find_partition(rucksacks: &[String], group_size: usize, table: &PriorityTable) -> (Option<Vec<Vec<usize>>>, usize) {
+    if !rucksacks.len().is_multiple_of(group_size) {
+        return (None, 0);
+    }
+
+    let sets: Vec<ItemSet> = rucksacks.iter().map(| r | ItemSet::from_items(r, table)).collect();
+    let groups = valid_groups(&sets, group_size);
+    let mut containing = vec![vec![]; rucksacks.len()];
+    for (id, group) in groups.iter().enumerate() {
+        group.iter().for_each(| r | containing[*r].push(id));
+    }
+
+    let mut search = Search {
+        groups: &groups,
+        containing,
+        assigned: vec![false; rucksacks.len()],
+        chosen: vec![],
+        nodes: 0,
+    };
+
+    let found = search.solve();
+    let partition = search.chosen.iter().map(| g | groups[*g].clone()).collect();
+    (if found { Some(partition) } else { None }, search.nodes)
+}
+
+// print the partition with the badge of each group and the sum of the badge priorities
+pub fn print_badges(input: &str, group_size: usize, table: &PriorityTable) {
+    let rucksacks: Vec<String> = input.lines().map(| l | l.to_string()).collect();
+
+    match find_partition(&rucksacks, group_size, table) {
+        (Some(groups), nodes) => {
+            let mut sum = 0;
+            for group in groups {
+                let badge = group
+                    .iter()
+                    .map(| i | ItemSet::from_items(&rucksacks[*i], table))
+                    .reduce(ItemSet::intersection)
+                    .unwrap();
+                let lines: Vec<String> = group.iter().map(| i | (i + 1).to_string()).collect();
+                println!("lines {} : badge {}", lines.join(", "), badge.items(table).collect::<String>());
+                sum += badge.priority_sum(table);
+            }
+            println!("priority sum : {} ({} nodes explored)", sum, nodes);
+        }
+        (None, _) if !rucksacks.len().is_multiple_of(group_size) => {
+            println!("no partition : {} rucksacks cannot be split in groups of {}", rucksacks.len(), group_size);
+        }
+        (None, nodes) => {
+            println!("no partition : every assignment was ruled out ({} nodes explored)", nodes);
+        }
+    }
+}
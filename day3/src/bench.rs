@@ -0,0 +1,100 @@
+// benchmark of the bitset solvers against the original string scanning ones
+// on large synthetic rucksacks
+
+use crate::priority::PriorityTable;
+use crate::{parse_input_part1, parse_input_part2, solve_part1, solve_part2};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+// original priority mapping : a to z => 1 to 26 and A to Z => 27 to 52
+fn map_char(c: char) -> i32 {
+    match c {
+        'a'..='z' => c as i32 - 'a' as i32 + 1,
+        'A'..='Z' => c as i32 - 'A' as i32 + 27,
+        _ => panic!("Invalid character {}", c)
+    }
+}
+
+// original implementation : scan s2 for every character of s1
+fn find_repeated_char(s1: &str, s2: &str) -> Option<char> {
+    s1.find(| c | s2.contains(c)).and_then(| i | s1.chars().nth(i))
+}
+
+fn find_repeated_char3(s1: &str, s2: &str, s3: &str) -> Option<char> {
+    s1.find(| c | s2.contains(c) && s3.contains(c)).and_then(| i | s1.chars().nth(i))
+}
+
+fn solve_part1_scan(input: &[(String, String)]) -> i32 {
+    input.iter().map(| (s1, s2) | find_repeated_char(s1, s2).map(map_char).unwrap_or(0)).sum()
+}
+
+fn solve_part2_scan(input: &[Vec<String>]) -> i32 {
+    input.iter().map(| g | find_repeated_char3(&g[0], &g[1], &g[2]).map(map_char).unwrap_or(0)).sum()
+}
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// draw `count` items from `pool`, with `shared` placed at the very end
+// (worst case for the scanning solvers)
+fn draw(rng: &mut StdRng, pool: &[char], count: usize, shared: char) -> String {
+    let mut s: String = (1..count).map(| _ | pool[rng.gen_range(0..pool.len())]).collect();
+    s.push(shared);
+    s
+}
+
+// generate groups of 3 rucksacks : each group shares exactly one badge,
+// and both compartments of a rucksack share exactly one item type
+fn generate(rucksacks: usize, items: usize, seed: u64) -> String {
+    // seeded, so that every run of the bench gets the same rucksacks
+    let mut rng = StdRng::seed_from_u64(seed);
+    let all: Vec<char> = ITEMS.chars().collect();
+    let mut lines = vec![];
+
+    while lines.len() < rucksacks {
+        let badge = all[rng.gen_range(0..all.len())];
+        let others: Vec<char> = all.iter().copied().filter(| c | *c != badge).collect();
+
+        // each rucksack of the group uses its own third of the other items, plus the badge
+        for third in others.chunks(others.len() / 3).take(3) {
+            let shared = third[rng.gen_range(0..third.len())];
+            let (left, right) = third.split_at(third.len() / 2);
+            let mut first = draw(&mut rng, left, items / 2 - 1, shared);
+            let second = draw(&mut rng, right, items / 2, shared);
+            first.push(badge);
+            lines.push(first + &second);
+        }
+    }
+
+    lines.truncate(rucksacks - rucksacks % 3);
+    lines.join("\n")
+}
+
+// time a solver, returning its answer and the elapsed time in milliseconds
+fn time<T>(f: impl FnOnce() -> T) -> (T, f64) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+pub fn run(rucksacks: usize, items: usize) {
+    // a compartment holds at least its shared item, and the badge or another item
+    if items < 4 {
+        panic!("Invalid number of items per rucksack : {} (at least 4)", items);
+    }
+    let input = generate(rucksacks, items, 42);
+    let input_part1 = parse_input_part1(&input);
+    let input_part2 = parse_input_part2(&input, 3).unwrap();
+    let table = PriorityTable::standard();
+    println!("{} rucksacks of {} items", input_part1.len(), items);
+
+    let (scan1, scan1_ms) = time(|| solve_part1_scan(&input_part1));
+    let (bits1, bits1_ms) = time(|| solve_part1(&input_part1, &table));
+    let (scan2, scan2_ms) = time(|| solve_part2_scan(&input_part2));
+    let (bits2, bits2_ms) = time(|| solve_part2(&input_part2, &table));
+
+    println!("part1 scan   : {} in {:.2} ms", scan1, scan1_ms);
+    println!("part1 bitset : {} in {:.2} ms", bits1, bits1_ms);
+    println!("part2 scan   : {} in {:.2} ms", scan2, scan2_ms);
+    println!("part2 bitset : {} in {:.2} ms", bits2, bits2_ms);
+}
@@ -0,0 +1,92 @@
+use crate::priority::PriorityTable;
+use std::slice;
+
+// set of item types, stored as a bitset where bit i is set when the item of index i in the priority table is present
+// a table of at most 64 item types, such as the standard one (a-z, A-Z), fits in a single word
+// kept inline, larger tables take one word per 64 item types
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ItemSet {
+    Word(u64),
+    Words(Vec<u64>),
+}
+
+impl Default for ItemSet {
+    fn default() -> ItemSet {
+        ItemSet::Word(0)
+    }
+}
+
+impl ItemSet {
+    fn words(&self) -> &[u64] {
+        match self {
+            ItemSet::Word(word) => slice::from_ref(word),
+            ItemSet::Words(words) => words,
+        }
+    }
+
+    fn words_mut(&mut self) -> &mut [u64] {
+        match self {
+            ItemSet::Word(word) => slice::from_mut(word),
+            ItemSet::Words(words) => words,
+        }
+    }
+
+    // combine two sets word by word, a missing word being empty
+    fn combine(self, other: ItemSet, op: fn(u64, u64) -> u64) -> ItemSet {
+        match (self, other) {
+            (ItemSet::Word(a), ItemSet::Word(b)) => ItemSet::Word(op(a, b)),
+            (a, b) => {
+                let (a, b) = (a.words(), b.words());
+                let word = | words: &[u64], i: usize | words.get(i).copied().unwrap_or(0);
+                ItemSet::Words((0..a.len().max(b.len())).map(| i | op(word(a, i), word(b, i))).collect())
+            }
+        }
+    }
+
+    // build the set of item types found in a compartment
+    pub fn from_items(items: &str, table: &PriorityTable) -> ItemSet {
+        let mut set = if table.len() <= 64 { ItemSet::Word(0) } else { ItemSet::Words(vec![0; table.len().div_ceil(64)]) };
+        let words = set.words_mut();
+        for item in items.chars() {
+            match table.index(item) {
+                Some(index) => words[index / 64] |= 1 << (index % 64),
+                None => panic!("Invalid character {}", item),
+            }
+        }
+        set
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        self.combine(other, | a, b | a & b)
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        self.combine(other, | a, b | a | b)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(| w | *w == 0)
+    }
+
+    // number of item types in the set
+    pub fn len(&self) -> usize {
+        self.words().iter().map(| w | w.count_ones() as usize).sum()
+    }
+
+    // indexes of the item types in the set, in increasing priority order
+    fn indexes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words().iter().enumerate().flat_map(| (word, bits) | {
+            (0..64).filter(move | bit | bits & 1 << bit != 0).map(move | bit | word * 64 + bit)
+        })
+    }
+
+    // item types in the set, in increasing priority order
+    pub fn items<'a>(&'a self, table: &'a PriorityTable) -> impl Iterator<Item = char> + 'a {
+        self.indexes().map(| i | table.symbol(i))
+    }
+
+    // sum of the priorities of every item type in the set
+    pub fn priority_sum(&self, table: &PriorityTable) -> i32 {
+        self.indexes().map(| i | table.priority(i)).sum()
+    }
+}
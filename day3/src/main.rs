@@ -1,67 +1,88 @@
+mod audit;
+mod badges;
+mod bench;
+mod item_set;
+mod priority;
+
+use item_set::ItemSet;
+use priority::PriorityTable;
+use std::env;
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
 
 // input type is a list two strings
 type InputPart1 = Vec<(String, String)>;
 
-// input type is a list three strings
-type InputPart2 = Vec<(String, String, String)>;
+// input type is a list of groups of strings
+type InputPart2 = Vec<Vec<String>>;
 
 // output type is an integer
 type Output = i32;
 
+// split a line in two compartments with the same number of items
+// items can be multi-byte characters, so the middle is found by counting characters
+fn split_compartments(l: &str) -> (&str, &str) {
+    let middle = l.char_indices().nth(l.chars().count() / 2).map(| (i, _) | i).unwrap_or(l.len());
+    l.split_at(middle)
+}
+
 // parse the input : String -> Input
 // format : multiple lines that needs to be split in the middle (no separator)
 fn parse_input_part1(input: &str) -> InputPart1 {
     input.lines().map(| l | {
-            let middle = l.len() / 2;
-            (l[..middle].to_string(), l[middle..].to_string())
+            let (s1, s2) = split_compartments(l);
+            (s1.to_string(), s2.to_string())
         }
     ).collect()
 }
 
-// parse the input : String -> Input
-// format : multiple lines grouped by 3 lines
-fn parse_input_part2(input: &str) -> InputPart2 {
-    let lines: Vec<&str> = input.lines().collect();
-    
-    lines.chunks(3).map(| l | {
-            (l[0].to_string(), l[1].to_string(), l[2].to_string())
-        }
-    ).collect()
+// error returned when the last group of rucksacks is not complete
+#[derive(Debug)]
+struct IncompleteGroup {
+    // line number (starting at 1) of the first rucksack of the group
+    line: usize,
+    found: usize,
+    expected: usize,
 }
 
-// map a to z => 1 to 26 and A to Z => 27 to 52
-fn map_char(c: char) -> i32 {
-    match c {
-        'a'..='z' => c as i32 - 'a' as i32 + 1,
-        'A'..='Z' => c as i32 - 'A' as i32 + 27,
-        _ => panic!("Invalid character {}", c)
+impl fmt::Display for IncompleteGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Incomplete group starting at line {} : {} rucksacks instead of {}", self.line, self.found, self.expected)
     }
 }
 
-fn find_repeated_char(s1: &str, s2: &str) -> Option<char> {
-    s1.find(| c | s2.contains(c)).and_then(| i | s1.chars().nth(i))
+// parse the input : String -> Input
+// format : multiple lines grouped by group_size lines
+fn parse_input_part2(input: &str, group_size: usize) -> Result<InputPart2, IncompleteGroup> {
+    let lines: Vec<&str> = input.lines().collect();
+
+    lines.chunks(group_size).enumerate().map(| (i, l) | {
+            if l.len() < group_size {
+                return Err(IncompleteGroup { line: i * group_size + 1, found: l.len(), expected: group_size });
+            }
+            Ok(l.iter().map(| s | s.to_string()).collect())
+        }
+    ).collect()
 }
 
 // solve the problem : Input -> Output
-//   foreach pair of strings, find the first character that is repeated in both strings and map it to a number then sum all numbers
-fn solve_part1(_input: &InputPart1) -> Output {
-    _input.iter().map(| (s1, s2) | {
-        find_repeated_char(s1, s2).map(map_char).unwrap_or(0)
+//   foreach pair of strings, find the item types present in both strings and sum their priorities
+fn solve_part1(input: &InputPart1, table: &PriorityTable) -> Output {
+    input.iter().map(| (s1, s2) | {
+        ItemSet::from_items(s1, table).intersection(ItemSet::from_items(s2, table)).priority_sum(table)
     }).sum()
 }
 
-fn find_repeated_char3(s1: &str, s2: &str, s3: &str) -> Option<char> {
-    s1.find(| c | s2.contains(c) && s3.contains(c)).and_then(| i | s1.chars().nth(i))
+// find the item types present in every rucksack of a group
+fn find_common_items(group: &[String], table: &PriorityTable) -> ItemSet {
+    group.iter().map(| s | ItemSet::from_items(s, table)).reduce(ItemSet::intersection).unwrap_or_default()
 }
 
 // solve the problem : Input -> Output
-//  foreach triplet of strings, find the first character that is repeated in all strings and map it to a number then sum all numbers
-fn solve_part2(input: &InputPart2) -> Output {
-    input.iter().map(| (s1, s2, s3) | {
-        find_repeated_char3(s1, s2, s3).map(map_char).unwrap_or(0)
-    }).sum()
+//  foreach group of strings, find the item types present in all strings and sum their priorities
+fn solve_part2(input: &InputPart2, table: &PriorityTable) -> Output {
+    input.iter().map(| group | find_common_items(group, table).priority_sum(table)).sum()
 }
 
 // print the output : Output -> ()
@@ -79,10 +100,67 @@ fn read_input() -> String {
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    // --group-size <n> and --priorities <file> can be anywhere on the command line
+    let flags: Vec<usize> = args
+        .iter()
+        .enumerate()
+        .filter(| (_, a) | *a == "--group-size" || *a == "--priorities")
+        .map(| (i, _) | i)
+        .collect();
+    let flag_value = | flag: &str | args.iter().position(| a | a == flag).map(| i | match args.get(i + 1) {
+        Some(value) => value.as_str(),
+        None => panic!("Missing value after {}", flag),
+    });
+    // the mode and its arguments are what remains once the flags and their values are removed
+    let positional: Vec<&str> = args
+        .iter()
+        .enumerate()
+        .filter(| (i, _) | !flags.iter().any(| f | i == f || *i == f + 1))
+        .map(| (_, a) | a.as_str())
+        .collect();
+    let mode = positional.get(1).copied();
+
+    if mode == Some("bench") {
+        // cargo run --release -- bench [rucksacks] [items per rucksack]
+        let rucksacks = positional.get(2).map(| s | s.parse::<usize>().unwrap()).unwrap_or(3000);
+        let items = positional.get(3).map(| s | s.parse::<usize>().unwrap()).unwrap_or(2000);
+        bench::run(rucksacks, items);
+        return;
+    }
+
+    // size of the groups of part 2 can be changed with --group-size <n>
+    let group_size = match flag_value("--group-size") {
+        Some(size) => size.parse::<usize>().unwrap(),
+        None => 3,
+    };
+    if group_size == 0 {
+        panic!("Invalid group size : 0");
+    }
+
+    // item priorities can be loaded with --priorities <file>
+    let table = match flag_value("--priorities") {
+        Some(file) => PriorityTable::load(file),
+        None => PriorityTable::standard(),
+    };
+
     let raw_input = read_input();
+    if mode == Some("audit") {
+        audit::print_audit(&raw_input, &table);
+        return;
+    }
+    if mode == Some("badges") {
+        badges::print_badges(&raw_input, group_size, &table);
+        return;
+    }
+
     let input_part1 = parse_input_part1(&raw_input);
-    let input_part2 = parse_input_part2(&raw_input);
-    let output_part1 = solve_part1(&input_part1);
-    let output_part2 = solve_part2(&input_part2);
+    let input_part2 = match parse_input_part2(&raw_input, group_size) {
+        Ok(input) => input,
+        Err(e) => panic!("{}", e),
+    };
+    let output_part1 = solve_part1(&input_part1, &table);
+    let output_part2 = solve_part2(&input_part2, &table);
     print_output(&output_part1, &output_part2);
 }
@@ -0,0 +1,124 @@
+// priority tables : which symbols are item types, and what each of them is worth
+//
+// a table can be loaded from a toml file :
+//   [priorities]
+//   "a-z" = 1
+//   "A-Z" = 27
+//   "0123456789" = 53
+//   "éèàç" = 63
+// a key is either a range of symbols ("a-z") or a list of symbols, and the
+// symbols get consecutive priorities starting from the value
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+// index value for ascii symbols that are not in the table
+const NOT_AN_ITEM: usize = usize::MAX;
+
+pub struct PriorityTable {
+    // symbols and priorities, sorted by priority : the index of a symbol is its position here
+    symbols: Vec<char>,
+    priorities: Vec<i32>,
+    // fast lookup for ascii symbols, and map for everything else
+    ascii_index: [usize; 128],
+    index: HashMap<char, usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PriorityConfig {
+    priorities: BTreeMap<String, i32>,
+}
+
+impl PriorityTable {
+    // build a table from (symbol, priority) pairs
+    fn from_entries(mut entries: Vec<(char, i32)>) -> PriorityTable {
+        entries.sort_by_key(| (symbol, priority) | (*priority, *symbol));
+
+        let mut table = PriorityTable {
+            symbols: vec![],
+            priorities: vec![],
+            ascii_index: [NOT_AN_ITEM; 128],
+            index: HashMap::new(),
+        };
+        for (i, (symbol, priority)) in entries.into_iter().enumerate() {
+            if table.index.insert(symbol, i).is_some() {
+                panic!("Item {} has several priorities", symbol);
+            }
+            if symbol.is_ascii() {
+                table.ascii_index[symbol as usize] = i;
+            }
+            table.symbols.push(symbol);
+            table.priorities.push(priority);
+        }
+        table
+    }
+
+    // a to z => 1 to 26 and A to Z => 27 to 52
+    pub fn standard() -> PriorityTable {
+        let lower = ('a'..='z').zip(1..);
+        let upper = ('A'..='Z').zip(27..);
+        PriorityTable::from_entries(lower.chain(upper).collect())
+    }
+
+    // parse a priority table : String -> PriorityTable
+    pub fn parse(content: &str) -> PriorityTable {
+        let config: PriorityConfig = match toml::from_str(content) {
+            Ok(config) => config,
+            Err(e) => panic!("Invalid priority config : {}", e),
+        };
+
+        let entries = config
+            .priorities
+            .iter()
+            .flat_map(| (key, first) | {
+                let chars: Vec<char> = key.chars().collect();
+                let symbols: Vec<char> = if chars.len() == 3 && chars[1] == '-' {
+                    (chars[0]..=chars[2]).collect()
+                } else {
+                    chars
+                };
+                symbols.into_iter().zip(*first..)
+            })
+            .collect();
+
+        let table = PriorityTable::from_entries(entries);
+        if table.is_empty() {
+            panic!("Priority config has no item");
+        }
+        table
+    }
+
+    // read a priority table file
+    pub fn load(path: &str) -> PriorityTable {
+        let content = fs::read_to_string(path).unwrap();
+        PriorityTable::parse(&content)
+    }
+
+    // number of item types in the table
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    // index of an item type, or None if the symbol is not an item
+    pub fn index(&self, symbol: char) -> Option<usize> {
+        if symbol.is_ascii() {
+            Some(self.ascii_index[symbol as usize]).filter(| i | *i != NOT_AN_ITEM)
+        } else {
+            self.index.get(&symbol).copied()
+        }
+    }
+
+    pub fn symbol(&self, index: usize) -> char {
+        self.symbols[index]
+    }
+
+    pub fn priority(&self, index: usize) -> i32 {
+        self.priorities[index]
+    }
+}
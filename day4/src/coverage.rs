@@ -0,0 +1,152 @@
+// cross pair analysis : every assignment of every line is indexed together, so
+// that elves can be compared with everyone and not only with their pair
+//
+// everything is done with sorts and sweeps, so the analysis is O(n log n)
+
+use crate::interval::{merge, Interval};
+use crate::parser::ReversedPolicy;
+use crate::{parse_input_part1, InputPart1};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+// an elf is identified by its line (starting at 1) and its side in the pair (0 or 1)
+type Elf = (usize, usize);
+
+struct Coverage {
+    elves: usize,
+    // elves sharing at least one section with another elf
+    overlapping: usize,
+    // elves sharing no section with anyone
+    isolated: Vec<Elf>,
+    // highest number of elves on a single section, and the intervals where it is reached
+    peak: usize,
+    peak_intervals: Vec<Interval<i64>>,
+    // sections between the lowest and the highest assigned ones that nobody covers
+    uncovered: Vec<Interval<i64>>,
+}
+
+fn analyse(input: &InputPart1) -> Coverage {
+    // an elf with several ranges has one entry per range
+    let assignments: Vec<(Elf, Interval<i64>)> = input
+        .iter()
+        .enumerate()
+        .flat_map(| (i, (a, b)) | [((i + 1, 0), a), ((i + 1, 1), b)])
+        .flat_map(| (elf, set) | set.intervals().iter().map(move | a | (elf, *a)))
+        .map(| (elf, a) | (elf, Interval::new(a.start() as i64, a.end() as i64).unwrap()))
+        .collect();
+    let mut ranges_per_elf: BTreeMap<Elf, usize> = BTreeMap::new();
+    assignments.iter().for_each(| (elf, _) | *ranges_per_elf.entry(*elf).or_insert(0) += 1);
+
+    // sorted by start, an elf overlaps an earlier one if some earlier end reaches its start,
+    // and a later one if the next start is before its end
+    let mut sorted: Vec<&(Elf, Interval<i64>)> = assignments.iter().collect();
+    sorted.sort_by_key(| (_, a) | (a.start(), a.end()));
+
+    // the ranges of an elf never touch each other, so any overlap is with another elf
+    let mut isolated_ranges: BTreeMap<Elf, usize> = BTreeMap::new();
+    let mut max_end = i64::MIN;
+    for (i, (elf, a)) in sorted.iter().enumerate() {
+        let overlaps_before = max_end >= a.start();
+        let overlaps_after = sorted.get(i + 1).is_some_and(| (_, next) | next.start() <= a.end());
+        if !overlaps_before && !overlaps_after {
+            *isolated_ranges.entry(*elf).or_insert(0) += 1;
+        }
+        max_end = max_end.max(a.end());
+    }
+    let isolated: Vec<Elf> = isolated_ranges
+        .into_iter()
+        .filter(| (elf, count) | ranges_per_elf[elf] == *count)
+        .map(| (elf, _) | elf)
+        .collect();
+
+    // sweep over start and end events : an elf covers [start, end + 1)
+    // at equal positions, ends are processed before starts
+    let mut events: Vec<(i64, i64)> = assignments
+        .iter()
+        .flat_map(| (_, a) | [(a.start(), 1), (a.end() + 1, -1)])
+        .collect();
+    events.sort();
+
+    let mut peak = 0;
+    let mut peak_intervals: Vec<Interval<i64>> = vec![];
+    let mut current = 0;
+    for (i, (position, delta)) in events.iter().enumerate() {
+        current += delta;
+        let next = match events.get(i + 1) {
+            Some((next, _)) if next > position => *next,
+            _ => continue,
+        };
+        let covered = current as usize;
+        if covered > peak {
+            peak = covered;
+            peak_intervals.clear();
+        }
+        if covered == peak && peak > 0 {
+            peak_intervals.push(Interval::new(*position, next - 1).unwrap());
+        }
+    }
+    let peak_intervals = merge(&peak_intervals);
+
+    let intervals: Vec<Interval<i64>> = assignments.iter().map(| (_, a) | *a).collect();
+    let uncovered = merge(&intervals)
+        .windows(2)
+        .filter_map(| w | Interval::new(w[0].end() + 1, w[1].start() - 1))
+        .collect();
+
+    Coverage {
+        elves: ranges_per_elf.len(),
+        overlapping: ranges_per_elf.len() - isolated.len(),
+        isolated,
+        peak,
+        peak_intervals,
+        uncovered,
+    }
+}
+
+fn join(intervals: &[Interval<i64>]) -> String {
+    intervals.iter().map(| i | i.to_string()).collect::<Vec<String>>().join(", ")
+}
+
+pub fn print_coverage(input: &InputPart1) {
+    let coverage = analyse(input);
+
+    println!("elves : {}", coverage.elves);
+    println!("elves overlapping someone : {}", coverage.overlapping);
+    let isolated: Vec<String> = coverage.isolated.iter().map(| (line, side) | format!("{}/{}", line, side + 1)).collect();
+    println!("elves overlapping nobody : {} [{}]", isolated.len(), isolated.join(", "));
+    println!("peak coverage : {} elves on sections {}", coverage.peak, join(&coverage.peak_intervals));
+    let uncovered: u64 = coverage.uncovered.iter().map(| i | i.length()).sum();
+    println!("uncovered sections : {} [{}]", uncovered, join(&coverage.uncovered));
+}
+
+// generate `pairs` lines of assignments spread over 40 * `pairs` sections, each at most 20 sections long
+// starts and lengths are drawn with a fixed seed, so every size of the bench always gets the same lines
+fn generate(pairs: usize) -> String {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut assignment = | | {
+        let start = rng.gen_range(1..=40 * pairs as u64);
+        format!("{}-{}", start, start + rng.gen_range(0..20))
+    };
+    (0..pairs).map(| _ | format!("{},{}", assignment(), assignment())).collect::<Vec<String>>().join("\n")
+}
+
+// time the analysis on generated inputs of growing size
+pub fn bench(max_pairs: usize) {
+    let mut pairs = 1000;
+    while pairs <= max_pairs {
+        let input = parse_input_part1(&generate(pairs), ReversedPolicy::Reject).unwrap();
+        let start = Instant::now();
+        let coverage = analyse(&input);
+        println!(
+            "{} assignments : peak {}, {} isolated, {} gaps in {:.1} ms",
+            coverage.elves,
+            coverage.peak,
+            coverage.isolated.len(),
+            coverage.uncovered.len(),
+            start.elapsed().as_secs_f64() * 1000.0
+        );
+        pairs *= 10;
+    }
+}
@@ -0,0 +1,239 @@
+// inclusive intervals of sections, with the usual set operations
+// and a classifier for the relation between two intervals
+
+use std::fmt;
+
+// integer types that can be used as section numbers
+pub trait Section: Copy + Ord + fmt::Display {
+    // next and previous section, None on overflow
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+    // number of sections from start to end, both included (start <= end)
+    fn count(start: Self, end: Self) -> u64;
+}
+
+macro_rules! impl_section {
+    ($($t:ty),*) => {
+        $(
+            impl Section for $t {
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn count(start: Self, end: Self) -> u64 {
+                    (end as i128 - start as i128 + 1) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_section!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+
+// relation between two intervals a and b, seen from a
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Relation {
+    // same sections
+    Equal,
+    // a contains every section of b
+    Contains,
+    // b contains every section of a
+    ContainedBy,
+    // a and b share some sections, but none contains the other
+    Overlaps,
+    // no shared section, but a ends right before b starts (or the opposite)
+    Adjacent,
+    // no shared section, with a gap between a and b
+    Disjoint,
+}
+
+// non empty interval of sections, both bounds included
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Interval<T: Section> {
+    start: T,
+    end: T,
+}
+
+impl<T: Section> Interval<T> {
+    // build an interval, None if start > end
+    pub fn new(start: T, end: T) -> Option<Interval<T>> {
+        if start <= end {
+            Some(Interval { start, end })
+        } else {
+            None
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    pub fn end(&self) -> T {
+        self.end
+    }
+
+    // number of sections in the interval
+    pub fn length(&self) -> u64 {
+        T::count(self.start, self.end)
+    }
+
+    // true if a ends right before b starts, or b right before a
+    fn is_adjacent(&self, other: &Interval<T>) -> bool {
+        self.end.succ() == Some(other.start) || other.end.succ() == Some(self.start)
+    }
+
+    pub fn relation(&self, other: &Interval<T>) -> Relation {
+        if self == other {
+            Relation::Equal
+        } else if self.start <= other.start && other.end <= self.end {
+            Relation::Contains
+        } else if other.start <= self.start && self.end <= other.end {
+            Relation::ContainedBy
+        } else if self.start <= other.end && other.start <= self.end {
+            Relation::Overlaps
+        } else if self.is_adjacent(other) {
+            Relation::Adjacent
+        } else {
+            Relation::Disjoint
+        }
+    }
+
+    // sections in both intervals
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // sections in either interval, None when the result is not a single interval
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        match self.relation(other) {
+            Relation::Disjoint => None,
+            _ => Interval::new(self.start.min(other.start), self.end.max(other.end)),
+        }
+    }
+
+    // sections in self but not in other : zero, one or two intervals
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if self.intersection(other).is_none() {
+            return vec![*self];
+        }
+
+        let before = other.start.pred().and_then(|end| Interval::new(self.start, end));
+        let after = other.end.succ().and_then(|start| Interval::new(start, self.end));
+        before.into_iter().chain(after).collect()
+    }
+}
+
+// merge overlapping and adjacent intervals : the result is sorted and has no shared or adjacent sections
+pub fn merge<T: Section>(intervals: &[Interval<T>]) -> Vec<Interval<T>> {
+    let mut sorted = intervals.to_vec();
+    sorted.sort_by_key(|i| (i.start, i.end));
+
+    let mut merged: Vec<Interval<T>> = vec![];
+    for interval in sorted {
+        match merged.last_mut().and_then(|last| last.union(&interval).map(|u| (last, u))) {
+            Some((last, union)) => *last = union,
+            None => merged.push(interval),
+        }
+    }
+    merged
+}
+
+impl<T: Section> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// set of sections made of several intervals
+// intervals are kept sorted and merged : none of them overlap or touch each other
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct IntervalSet<T: Section> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Section> IntervalSet<T> {
+    pub fn new(intervals: &[Interval<T>]) -> IntervalSet<T> {
+        IntervalSet { intervals: merge(intervals) }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    // the set is a single interval
+    pub fn as_interval(&self) -> Option<Interval<T>> {
+        match self.intervals.as_slice() {
+            [interval] => Some(*interval),
+            _ => None,
+        }
+    }
+
+    // number of sections in the set
+    pub fn length(&self) -> u64 {
+        self.intervals.iter().map(|i| i.length()).sum()
+    }
+
+    // every section of self is in other
+    pub fn is_subset(&self, other: &IntervalSet<T>) -> bool {
+        self.intervals.iter().all(|i| {
+            other
+                .intervals
+                .iter()
+                .any(|o| matches!(o.relation(i), Relation::Equal | Relation::Contains))
+        })
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let intervals: Vec<Interval<T>> = self
+            .intervals
+            .iter()
+            .flat_map(|i| other.intervals.iter().filter_map(move |o| i.intersection(o)))
+            .collect();
+        IntervalSet::new(&intervals)
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let intervals: Vec<Interval<T>> = self
+            .intervals
+            .iter()
+            .flat_map(|i| {
+                other.intervals.iter().fold(vec![*i], |remaining, o| {
+                    remaining.iter().flat_map(|r| r.difference(o)).collect()
+                })
+            })
+            .collect();
+        IntervalSet::new(&intervals)
+    }
+
+    // same classification as for intervals, applied to the sets of sections
+    pub fn relation(&self, other: &IntervalSet<T>) -> Relation {
+        if self == other {
+            Relation::Equal
+        } else if other.is_subset(self) {
+            Relation::Contains
+        } else if self.is_subset(other) {
+            Relation::ContainedBy
+        } else if self.intersection(other).length() > 0 {
+            Relation::Overlaps
+        } else {
+            // without shared sections, merging only joins intervals that touch each other
+            let all: Vec<Interval<T>> = self.intervals.iter().chain(other.intervals.iter()).copied().collect();
+            if merge(&all).len() < all.len() {
+                Relation::Adjacent
+            } else {
+                Relation::Disjoint
+            }
+        }
+    }
+}
+
+impl<T: Section> fmt::Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let intervals: Vec<String> = self.intervals.iter().map(|i| i.to_string()).collect();
+        write!(f, "{}", intervals.join(";"))
+    }
+}
@@ -1,8 +1,15 @@
+mod coverage;
+mod interval;
+mod parser;
+mod rebalance;
+
+use interval::{merge, Interval, IntervalSet, Relation};
+use parser::{parse_pairs, ParseError, ReversedPolicy};
+use std::env;
 use std::fs::File;
 use std::io::Read;
-use std::ops::RangeInclusive;
 
-type InputPart1 = Vec<(RangeInclusive<i32>,RangeInclusive<i32>)>;
+type InputPart1 = Vec<(IntervalSet<i32>, IntervalSet<i32>)>;
 
 type InputPart2 = InputPart1;
 
@@ -11,26 +18,25 @@ type Output = usize;
 
 // parse the input : String -> Input
 // format : 1-3,5-8
-fn parse_input_part1(input: &str) -> InputPart1 {
-    input.lines().map(| l | {
-        let values: Vec<i32> = l.replace(",", "-").split('-').map(| s | s.parse::<i32>().unwrap()).collect();
-        
-        (values[0]..=values[1], values[2]..=values[3])
-    }).collect()
+//   each side can also list several ranges : 2-4;7-9,3-5
+fn parse_input_part1(input: &str, policy: ReversedPolicy) -> Result<InputPart1, ParseError> {
+    parse_pairs(input, policy)
 }
 
-const parse_input_part2: fn(&str) -> InputPart2 = parse_input_part1;
+const PARSE_INPUT_PART2: fn(&str, ReversedPolicy) -> Result<InputPart2, ParseError> = parse_input_part1;
 
-fn is_range_included_inside(a: &RangeInclusive<i32>, b: &RangeInclusive<i32>) -> bool {
-    a.contains(&b.start()) && a.contains(&b.end()) || b.contains(&a.start()) && b.contains(&a.end())
+// one of the assignments is fully contained in the other
+fn is_range_included_inside(a: &IntervalSet<i32>, b: &IntervalSet<i32>) -> bool {
+    matches!(a.relation(b), Relation::Equal | Relation::Contains | Relation::ContainedBy)
 }
 
 fn solve_part1(input: &InputPart1) -> Output {
     input.iter().filter(| (a, b) | is_range_included_inside(a, b)).count()
 }
 
-fn is_range_overlapping(a: &RangeInclusive<i32>, b: &RangeInclusive<i32>) -> bool {
-    a.contains(&b.start()) || a.contains(&(b.end())) || b.contains(&a.start()) || b.contains(&(a.end()))
+// the assignments share at least one section
+fn is_range_overlapping(a: &IntervalSet<i32>, b: &IntervalSet<i32>) -> bool {
+    !matches!(a.relation(b), Relation::Adjacent | Relation::Disjoint)
 }
 
 fn solve_part2(input: &InputPart2) -> Output {
@@ -51,10 +57,76 @@ fn read_input() -> String {
     contents
 }
 
+// print how many pairs fall in each relation, and how many sections are shared or assigned to a single elf
+fn print_relations(input: &InputPart1) {
+    let relations = [
+        Relation::Equal,
+        Relation::Contains,
+        Relation::ContainedBy,
+        Relation::Overlaps,
+        Relation::Adjacent,
+        Relation::Disjoint,
+    ];
+    for relation in relations {
+        let count = input.iter().filter(| (a, b) | a.relation(b) == relation).count();
+        println!("{:?} : {}", relation, count);
+    }
+
+    let shared: u64 = input.iter().map(| (a, b) | a.intersection(b).length()).sum();
+    let exclusive: u64 = input.iter().map(| (a, b) | a.difference(b).length() + b.difference(a).length()).sum();
+    let all: Vec<Interval<i32>> = input
+        .iter()
+        .flat_map(| (a, b) | a.intervals().iter().chain(b.intervals()).copied())
+        .collect();
+    let covered: u64 = merge(&all).iter().map(| i | i.length()).sum();
+
+    println!("sections shared within a pair : {}", shared);
+    println!("sections assigned to a single elf of a pair : {}", exclusive);
+    println!("sections assigned to anyone : {}", covered);
+}
+
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    // the mode and its arguments are what remains once the --normalize flag is removed
+    let positional: Vec<&str> = args.iter().map(| a | a.as_str()).filter(| a | *a != "--normalize").collect();
+    let mode = positional.get(1).copied();
+
+    if mode == Some("bench") {
+        // cargo run --release -- bench [max pairs]
+        let max_pairs = positional.get(2).map(| s | s.parse::<usize>().unwrap()).unwrap_or(1_000_000);
+        coverage::bench(max_pairs);
+        return;
+    }
+
+    // reversed ranges are rejected, unless --normalize is given, anywhere on the command line
+    let policy = if args.iter().any(| a | a == "--normalize") { ReversedPolicy::Normalize } else { ReversedPolicy::Reject };
+
     let raw_input = read_input();
-    let input_part1 = parse_input_part1(&raw_input);
-    let input_part2 = parse_input_part2(&raw_input);
+    let input_part1 = match parse_input_part1(&raw_input, policy) {
+        Ok(input) => input,
+        Err(e) => panic!("{}", e),
+    };
+    if mode == Some("relations") {
+        print_relations(&input_part1);
+        return;
+    }
+    if mode == Some("coverage") {
+        coverage::print_coverage(&input_part1);
+        return;
+    }
+    if mode == Some("rebalance") {
+        // cargo run -- rebalance [containments|overlaps] [output file]
+        let goal = match positional.get(2).copied() {
+            None | Some("containments") => rebalance::Goal::Containments,
+            Some("overlaps") => rebalance::Goal::Overlaps,
+            Some(x) => panic!("Unknown rebalancing goal : {}", x),
+        };
+        let output = positional.get(3).copied().unwrap_or("rebalanced.txt");
+        rebalance::rebalance(&input_part1, goal, output);
+        return;
+    }
+
+    let input_part2 = PARSE_INPUT_PART2(&raw_input, policy).unwrap();
     let output_part1 = solve_part1(&input_part1);
     let output_part2 = solve_part2(&input_part2);
     print_output(&output_part1, &output_part2);
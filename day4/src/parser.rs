@@ -0,0 +1,143 @@
+// parser for assignment pairs : a-b,c-d
+//
+// bounds can be any integer type, negative bounds included (-5--2,3-4),
+// each side can be a list of ranges separated by ';' (2-4;7-9,3-5),
+// and errors are reported with the line and column where parsing failed
+
+use crate::interval::{Interval, IntervalSet, Section};
+use std::fmt;
+use std::str::FromStr;
+
+// what to do with a range written backwards, like 8-3
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReversedPolicy {
+    // report the range as an error
+    Reject,
+    // swap the bounds : 8-3 is read as 3-8
+    Normalize,
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    // position of the error, both starting at 1
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {} : {}", self.line, self.column, self.message)
+    }
+}
+
+// the two assignments of a line
+pub type Pair<T> = (IntervalSet<T>, IntervalSet<T>);
+
+// cursor over the characters of a line
+struct LineParser<'a> {
+    line: usize,
+    chars: Vec<(usize, char)>,
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> LineParser<'a> {
+    fn new(line: usize, text: &'a str) -> LineParser<'a> {
+        LineParser { line, chars: text.char_indices().collect(), text, position: 0 }
+    }
+
+    fn error<T>(&self, column: usize, message: String) -> Result<T, ParseError> {
+        Err(ParseError { line: self.line, column: column + 1, message })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).map(| (_, c) | *c)
+    }
+
+    fn byte_offset(&self) -> usize {
+        self.chars.get(self.position).map(| (i, _) | *i).unwrap_or(self.text.len())
+    }
+
+    fn describe_next(&self) -> String {
+        match self.peek() {
+            Some(c) => format!("'{}'", c),
+            None => "end of line".to_string(),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            Ok(())
+        } else {
+            self.error(self.position, format!("expected '{}', found {}", expected, self.describe_next()))
+        }
+    }
+
+    // number : optional minus sign followed by digits
+    fn number<T: FromStr>(&mut self) -> Result<(T, usize), ParseError> {
+        let column = self.position;
+        let start = self.byte_offset();
+        if self.peek() == Some('-') {
+            self.position += 1;
+        }
+        let digits = self.position;
+        while self.peek().is_some_and(| c | c.is_ascii_digit()) {
+            self.position += 1;
+        }
+        if self.position == digits {
+            return self.error(self.position, format!("expected a number, found {}", self.describe_next()));
+        }
+
+        let text = &self.text[start..self.byte_offset()];
+        match text.parse::<T>() {
+            Ok(value) => Ok((value, column)),
+            Err(_) => self.error(column, format!("number {} is out of range", text)),
+        }
+    }
+
+    // range : number '-' number
+    fn range<T: Section + FromStr>(&mut self, policy: ReversedPolicy) -> Result<Interval<T>, ParseError> {
+        let (start, column) = self.number::<T>()?;
+        self.expect('-')?;
+        let (end, _) = self.number::<T>()?;
+
+        match (Interval::new(start, end), policy) {
+            (Some(interval), _) => Ok(interval),
+            (None, ReversedPolicy::Normalize) => Ok(Interval::new(end, start).unwrap()),
+            (None, ReversedPolicy::Reject) => self.error(column, format!("range {}-{} is reversed", start, end)),
+        }
+    }
+
+    // ranges : range (';' range)*
+    fn ranges<T: Section + FromStr>(&mut self, policy: ReversedPolicy) -> Result<IntervalSet<T>, ParseError> {
+        let mut intervals = vec![self.range(policy)?];
+        while self.peek() == Some(';') {
+            self.position += 1;
+            intervals.push(self.range(policy)?);
+        }
+        Ok(IntervalSet::new(&intervals))
+    }
+
+    // pair : ranges ',' ranges
+    fn pair<T: Section + FromStr>(&mut self, policy: ReversedPolicy) -> Result<Pair<T>, ParseError> {
+        let first = self.ranges(policy)?;
+        self.expect(',')?;
+        let second = self.ranges(policy)?;
+        if self.peek().is_some() {
+            return self.error(self.position, format!("unexpected {} after the second range", self.describe_next()));
+        }
+        Ok((first, second))
+    }
+}
+
+// parse the input : String -> list of pairs
+// format : one pair per line, 1-3,5-8 or 1-3;6-7,5-8
+pub fn parse_pairs<T: Section + FromStr>(input: &str, policy: ReversedPolicy) -> Result<Vec<Pair<T>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(| (i, l) | LineParser::new(i + 1, l).pair(policy))
+        .collect()
+}
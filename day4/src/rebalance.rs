@@ -0,0 +1,156 @@
+// rebalancing of the assignments : for each pair, find the smallest change
+// that removes a containment (or any overlap) between the two elves
+//
+// a change is scored by the number of sections changed : sections removed
+// from an elf plus sections added to it. every elf keeps at least one section
+//
+// a pair where both elves have a single range keeps a single range per elf, the other
+// pairs are rebalanced as sets of sections
+
+use crate::interval::{Interval, IntervalSet};
+use crate::{is_range_included_inside, is_range_overlapping, InputPart1};
+use std::fmt::Display;
+use std::fs;
+
+// what the rebalancing has to remove
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Goal {
+    Containments,
+    Overlaps,
+}
+
+impl Goal {
+    fn is_violated(&self, a: &IntervalSet<i32>, b: &IntervalSet<i32>) -> bool {
+        match self {
+            Goal::Containments => is_range_included_inside(a, b),
+            Goal::Overlaps => is_range_overlapping(a, b),
+        }
+    }
+}
+
+// sections changed to turn `from` into `to` : size of the symmetric difference
+fn cost(from: &Interval<i32>, to: &Interval<i32>) -> u64 {
+    match from.intersection(to) {
+        Some(shared) => from.length() + to.length() - 2 * shared.length(),
+        None => from.length() + to.length(),
+    }
+}
+
+// sections of `to` outside of the sections the pair had before, used to break ties
+fn spill(to: &Interval<i32>, a: &Interval<i32>, b: &Interval<i32>) -> u64 {
+    let span = Interval::new(a.start().min(b.start()), a.end().max(b.end())).unwrap();
+    to.difference(&span).iter().map(| i | i.length()).sum()
+}
+
+// find the cheapest pair of assignments meeting the goal
+// every constraint between two assignments is a strict inequality between bounds, so an
+// optimal solution only uses the original bounds shifted by at most 2
+fn rebalance_pair(a: &Interval<i32>, b: &Interval<i32>, goal: Goal) -> (Interval<i32>, Interval<i32>, u64) {
+    let violates = | a: &Interval<i32>, b: &Interval<i32> | goal.is_violated(&IntervalSet::new(&[*a]), &IntervalSet::new(&[*b]));
+    if !violates(a, b) {
+        return (*a, *b, 0);
+    }
+
+    let mut bounds: Vec<i32> = [a.start(), a.end(), b.start(), b.end()]
+        .iter()
+        .flat_map(| v | (-2..=2).filter_map(move | d | v.checked_add(d)))
+        .collect();
+    bounds.sort();
+    bounds.dedup();
+    let candidates: Vec<Interval<i32>> = bounds
+        .iter()
+        .flat_map(| start | bounds.iter().filter_map(move | end | Interval::new(*start, *end)))
+        .collect();
+
+    // (cost, spill) of the best pair found so far
+    let mut best_score = (u64::MAX, u64::MAX);
+    let mut best = (*a, *b);
+    for new_a in &candidates {
+        let cost_a = cost(a, new_a);
+        for new_b in &candidates {
+            let score = (cost_a + cost(b, new_b), spill(new_a, a, b) + spill(new_b, a, b));
+            if score < best_score && !violates(new_a, new_b) {
+                best_score = score;
+                best = (*new_a, *new_b);
+            }
+        }
+    }
+
+    (best.0, best.1, best_score.0)
+}
+
+// one section
+fn section(value: i32) -> IntervalSet<i32> {
+    IntervalSet::new(&[Interval::new(value, value).unwrap()])
+}
+
+// find the cheapest pair of section sets meeting the goal, when an elf has several ranges
+// sections can then be removed anywhere : only shared sections are removed to remove an overlap,
+// and a section from each elf at most to remove a containment. equal sets have at least two sections,
+// since one of them has several ranges
+fn rebalance_sets(a: &IntervalSet<i32>, b: &IntervalSet<i32>, goal: Goal) -> (IntervalSet<i32>, IntervalSet<i32>, u64) {
+    if !goal.is_violated(a, b) {
+        return (a.clone(), b.clone(), 0);
+    }
+
+    let first = | set: &IntervalSet<i32> | section(set.intervals()[0].start());
+    let last = | set: &IntervalSet<i32> | section(set.intervals().last().unwrap().end());
+    match goal {
+        // the shared sections are removed from the elf with more sections, unless it would keep none
+        Goal::Overlaps => {
+            let shared = a.intersection(b);
+            let (rest_a, rest_b) = (a.difference(&shared), b.difference(&shared));
+            if rest_b.length() > 0 && (b.length() >= a.length() || rest_a.length() == 0) {
+                (a.clone(), rest_b, shared.length())
+            } else if rest_a.length() > 0 {
+                (rest_a, b.clone(), shared.length())
+            } else {
+                // same sections : the last one is left to b alone
+                (a.difference(&last(a)), last(b), shared.length())
+            }
+        }
+        // the first section of the smaller elf is removed from the larger one, and equal
+        // elves each lose a different section
+        Goal::Containments => {
+            if a == b {
+                (a.difference(&last(a)), b.difference(&first(b)), 2)
+            } else if a.is_subset(b) {
+                (a.clone(), b.difference(&first(a)), 1)
+            } else {
+                (a.difference(&first(b)), b.clone(), 1)
+            }
+        }
+    }
+}
+
+fn format_pair(a: &impl Display, b: &impl Display) -> String {
+    format!("{},{}", a, b)
+}
+
+// rebalance every pair, write the rewritten assignments to `output` and print the diff
+pub fn rebalance(input: &InputPart1, goal: Goal, output: &str) {
+    let mut lines = vec![];
+    let mut changed = 0;
+    let mut total_cost = 0;
+
+    for (i, (a, b)) in input.iter().enumerate() {
+        let (new_a, new_b, cost) = match (a.as_interval(), b.as_interval()) {
+            (Some(a), Some(b)) => {
+                let (new_a, new_b, cost) = rebalance_pair(&a, &b, goal);
+                (IntervalSet::new(&[new_a]), IntervalSet::new(&[new_b]), cost)
+            }
+            _ => rebalance_sets(a, b, goal),
+        };
+        if cost > 0 {
+            println!("@@ line {} ({} sections changed)", i + 1, cost);
+            println!("-{}", format_pair(a, b));
+            println!("+{}", format_pair(&new_a, &new_b));
+            changed += 1;
+            total_cost += cost;
+        }
+        lines.push(format_pair(&new_a, &new_b));
+    }
+
+    fs::write(output, lines.join("\n") + "\n").unwrap();
+    println!("{} pairs rewritten, {} sections changed, written to {}", changed, total_cost, output);
+}
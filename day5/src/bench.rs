@@ -0,0 +1,206 @@
+// benchmark of the crane executors against the original element by element ones, on
+// large synthetic inputs, and of the ways of taking crates off a stack and putting them on another one
+
+use crate::crane::{Crane, CrateMover9000, CrateMover9001, APPEND_THRESHOLD, SPLIT_THRESHOLD};
+use crate::{exec_instruction, exec_instruction_mut, Instruction, Stacks};
+use imbl::vector::Vector;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::time::Instant;
+
+// original CrateMover 9000 : pops and pushes the crates one at a time
+struct LoopMover9000;
+
+impl Crane for LoopMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000 (loop)".to_string()
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        for _ in 0..instruction.count {
+            let elem = from.pop_back().unwrap();
+            to.push_back(elem);
+        }
+
+        instruction.count as u64
+    }
+}
+
+// original CrateMover 9001 : goes through a temporary stack
+struct LoopMover9001;
+
+impl Crane for LoopMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001 (loop)".to_string()
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let mut temp = Vector::new();
+
+        for _ in 0..instruction.count {
+            let elem = from.pop_back().unwrap();
+            temp.push_back(elem);
+        }
+
+        for _ in 0..instruction.count {
+            let elem = temp.pop_back().unwrap();
+            to.push_back(elem);
+        }
+
+        1
+    }
+}
+
+// CrateMover 9001 always taking the crates off the same way, and putting them on the same way
+struct FixedMover9001 {
+    split: bool,
+    append: bool,
+}
+
+impl Crane for FixedMover9001 {
+    fn name(&self) -> String {
+        let take = if self.split { "split" } else { "pop" };
+        let put = if self.append { "append" } else { "extend" };
+        format!("CrateMover 9001 ({}/{})", take, put)
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let moved = if self.split {
+            from.split_off(from.len() - instruction.count)
+        } else {
+            let mut moved = Vector::new();
+            for _ in 0..instruction.count {
+                moved.push_front(from.pop_back().unwrap());
+            }
+            moved
+        };
+        if self.append {
+            to.append(moved);
+        } else {
+            to.extend(moved);
+        }
+
+        1
+    }
+}
+
+const STACKS: usize = 10;
+const LARGE_MOVE: usize = 1000;
+
+// stacks of `crates` numbered crates, and `moves` legal instructions
+// most moves take 1 to 100 crates like the puzzle, and one in ten takes LARGE_MOVE to `max_count`
+// crates, where taking the crates off one at a time would be much slower than splitting the stack
+fn generate(moves: usize, crates: usize, max_count: usize) -> (Vec<Instruction>, Stacks<u32>) {
+    // fixed seed : every run of the bench moves the same crates
+    let mut rng = StdRng::seed_from_u64(42);
+    let state: Stacks<u32> = (0..STACKS)
+        .map(|stack| (0..crates).map(|c| (stack * crates + c) as u32).collect())
+        .collect();
+
+    // only the heights are needed to keep the instructions legal
+    let mut heights = [crates; STACKS];
+    let instructions = (0..moves)
+        .map(|line| {
+            let from = rng.gen_range(0..STACKS);
+            let to = (from + 1 + rng.gen_range(0..STACKS - 1)) % STACKS;
+            let count = if line % 10 == 9 {
+                rng.gen_range(LARGE_MOVE..=max_count.max(LARGE_MOVE))
+            } else {
+                rng.gen_range(1..=100)
+            };
+            let count = count.min(heights[from]);
+            heights[from] -= count;
+            heights[to] += count;
+            Instruction { count, from, to, line: line + 1 }
+        })
+        .collect();
+
+    (instructions, state)
+}
+
+// execute the instructions in place, returning the final state and the elapsed time in milliseconds
+fn time_mut<C: Crane>(crane: &C, instructions: &[Instruction], state: &Stacks<u32>) -> (Stacks<u32>, f64) {
+    let start = Instant::now();
+    let mut state = state.clone();
+    for instruction in instructions {
+        exec_instruction_mut(crane, instruction, &mut state).unwrap_or_else(|e| panic!("{}", e));
+    }
+    (state, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+// same, building a new persistent state at every step
+fn time_persistent<C: Crane>(crane: &C, instructions: &[Instruction], state: &Stacks<u32>) -> (Stacks<u32>, f64) {
+    let start = Instant::now();
+    let state = instructions.iter().fold(state.clone(), |state, instruction| {
+        exec_instruction(crane, instruction, &state).unwrap_or_else(|e| panic!("{}", e)).0
+    });
+    (state, start.elapsed().as_secs_f64() * 1000.0)
+}
+
+fn print_time<C: Crane>(crane: &C, executor: &str, moves: usize, ms: f64) {
+    println!("{:<24} {:<10} : {} moves in {:.1} ms ({:.0} ns/move)", crane.name(), executor, moves, ms, ms * 1e6 / moves as f64);
+}
+
+// moves of a fixed number of crates back and forth between two stacks, to check where SPLIT_THRESHOLD
+// and APPEND_THRESHOLD stand : taking the crates off one at a time against splitting the stack, then
+// pushing them one at a time against appending them
+fn compare_thresholds(crates: usize) {
+    println!("moving crates one at a time against splitting and appending, stacks of {} crates :", crates);
+    let state: Stacks<u32> = (0..2).map(|stack| (0..crates).map(|c| (stack * crates + c) as u32).collect()).collect();
+    let movers = [
+        FixedMover9001 { split: false, append: false },
+        FixedMover9001 { split: true, append: false },
+        FixedMover9001 { split: true, append: true },
+    ];
+
+    for count in [1, 2, 4, 8, 16, 64, 256, 1024, 4096, 16384].into_iter().filter(|count| *count <= crates) {
+        let instructions: Vec<Instruction> = (0..200)
+            .map(|line| Instruction { count, from: line % 2, to: 1 - line % 2, line: line + 1 })
+            .collect();
+        let results: Vec<(Stacks<u32>, f64)> = movers.iter().map(|mover| time_mut(mover, &instructions, &state)).collect();
+        if results.iter().any(|(result, _)| *result != results[0].0) {
+            panic!("Pop, split and append movers differ");
+        }
+        println!(
+            "  {:>5} crates : {:>7.0} ns/move pop/extend, {:>7.0} ns/move split/extend, {:>7.0} ns/move split/append",
+            count,
+            results[0].1 * 1e6 / 200.0,
+            results[1].1 * 1e6 / 200.0,
+            results[2].1 * 1e6 / 200.0
+        );
+    }
+    println!("thresholds : split from {} crates, append from {} crates", SPLIT_THRESHOLD, APPEND_THRESHOLD);
+    println!();
+}
+
+// the loop movers are O(count) per move, so they only run on the first moves
+pub fn run(moves: usize, crates: usize, max_count: usize) {
+    compare_thresholds(crates);
+
+    let (instructions, state) = generate(moves, crates, max_count);
+    let prefix = &instructions[..moves.min(1000)];
+    println!(
+        "{} stacks of {} crates, {} moves of 1 to 100 crates, one in ten of {} to {} crates",
+        STACKS, crates, moves, LARGE_MOVE, max_count
+    );
+
+    let (state9001, ms) = time_mut(&CrateMover9001, &instructions, &state);
+    print_time(&CrateMover9001, "in place", moves, ms);
+    let (persistent9001, ms) = time_persistent(&CrateMover9001, &instructions, &state);
+    print_time(&CrateMover9001, "persistent", moves, ms);
+    if persistent9001 != state9001 {
+        panic!("In place and persistent executors differ");
+    }
+
+    let (split9001, ms) = time_mut(&CrateMover9001, prefix, &state);
+    print_time(&CrateMover9001, "in place", prefix.len(), ms);
+    let (loop9001, ms) = time_mut(&LoopMover9001, prefix, &state);
+    print_time(&LoopMover9001, "in place", prefix.len(), ms);
+    let (split9000, ms) = time_mut(&CrateMover9000, prefix, &state);
+    print_time(&CrateMover9000, "in place", prefix.len(), ms);
+    let (loop9000, ms) = time_mut(&LoopMover9000, prefix, &state);
+    print_time(&LoopMover9000, "in place", prefix.len(), ms);
+    if split9001 != loop9001 || split9000 != loop9000 {
+        panic!("Split and loop movers differ");
+    }
+}
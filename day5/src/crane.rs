@@ -0,0 +1,238 @@
+// crane models : how a crane moves crates from one stack to another, and what it costs
+// large moves split and append the persistent vectors, so taking or putting crates is O(log n) whatever
+// their number, only the 9000 goes through every moved crate to reverse them
+// the stacks are imbl vectors : the tree concatenation of im 15.1 builds invalid trees (see the tests)
+
+use crate::Instruction;
+use imbl::vector::Vector;
+use std::mem;
+
+// below these numbers of crates, popping them one at a time is faster than splitting the tree, and
+// pushing them one at a time is faster than concatenating the trees
+// (measured by the bench mode with stacks of 10^5 crates, appending pays off sooner on smaller stacks)
+pub const SPLIT_THRESHOLD: usize = 8;
+pub const APPEND_THRESHOLD: usize = 1024;
+
+// take the `count` crates on top of the stack, keeping their order
+pub fn take_top<T: Clone>(from: &mut Vector<T>, count: usize) -> Vector<T> {
+    if count < SPLIT_THRESHOLD {
+        let mut moved = Vector::new();
+        for _ in 0..count {
+            moved.push_front(from.pop_back().unwrap());
+        }
+        moved
+    } else {
+        from.split_off(from.len() - count)
+    }
+}
+
+// put the moved crates on top of the stack, keeping their order
+pub fn put_on<T: Clone>(to: &mut Vector<T>, moved: Vector<T>) {
+    if moved.len() < APPEND_THRESHOLD {
+        to.extend(moved);
+    } else {
+        to.append(moved);
+    }
+}
+
+pub trait Crane {
+    fn name(&self) -> String;
+
+    // move instruction.count crates from the top of `from` to the top of `to`
+    // returns the cost of the move
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64;
+
+    // move instruction.count crates from a stack onto itself, returns the cost of the move
+    // a crane taking the crates from the top puts them back where they were
+    fn move_within<T: Clone>(&self, stack: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let mut moved = Vector::new();
+        self.move_crates(&mut stack.clone(), &mut moved, instruction)
+    }
+}
+
+// moves crates one at a time, so the moved crates end up in reverse order
+// each crate is a lift of cost 1
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        if instruction.count < SPLIT_THRESHOLD {
+            for _ in 0..instruction.count {
+                let elem = from.pop_back().unwrap();
+                to.push_back(elem);
+            }
+        } else {
+            // reversing the order needs to go through every moved crate anyway
+            let moved = from.split_off(from.len() - instruction.count);
+            to.extend(moved.iter().rev().cloned());
+        }
+
+        instruction.count as u64
+    }
+}
+
+// moves all the crates at once, keeping their order, for a cost of 1
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let moved = take_top(from, instruction.count);
+        put_on(to, moved);
+
+        1
+    }
+}
+
+// moves at most `capacity` crates at once, keeping their order
+// larger moves are split in several lifts of cost 1
+pub struct LimitedCapacity {
+    capacity: usize,
+}
+
+impl LimitedCapacity {
+    // a crane lifting no crate would never finish a move
+    pub fn new(capacity: usize) -> LimitedCapacity {
+        if capacity == 0 {
+            panic!("Invalid crane capacity : 0");
+        }
+        LimitedCapacity { capacity }
+    }
+}
+
+impl Crane for LimitedCapacity {
+    fn name(&self) -> String {
+        format!("limited capacity ({})", self.capacity)
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let mut remaining = instruction.count;
+        let mut lifts = 0;
+
+        while remaining > 0 {
+            let count = remaining.min(self.capacity);
+            let lift = Instruction { count, ..*instruction };
+            CrateMover9001.move_crates(from, to, &lift);
+            remaining -= count;
+            lifts += 1;
+        }
+
+        lifts
+    }
+}
+
+// takes the crates from the bottom of the stack, and puts them on top of the
+// destination keeping their order, for a cost of 1
+pub struct BottomMover;
+
+impl Crane for BottomMover {
+    fn name(&self) -> String {
+        "bottom mover".to_string()
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let rest = from.split_off(instruction.count);
+        put_on(to, mem::replace(from, rest));
+
+        1
+    }
+
+    // the bottom crates end up on top of the same stack
+    fn move_within<T: Clone>(&self, stack: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        let mut moved = Vector::new();
+        let cost = self.move_crates(stack, &mut moved, instruction);
+        put_on(stack, moved);
+        cost
+    }
+}
+
+// moves crates like another crane, but accounts the cost of each move as a fixed
+// cost, plus a cost per crate, plus a cost per stack travelled
+pub struct Metered<C: Crane> {
+    pub crane: C,
+    pub per_move: u64,
+    pub per_crate: u64,
+    pub per_stack: u64,
+}
+
+impl<C: Crane> Crane for Metered<C> {
+    fn name(&self) -> String {
+        format!("metered {}", self.crane.name())
+    }
+
+    fn move_crates<T: Clone>(&self, from: &mut Vector<T>, to: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        self.crane.move_crates(from, to, instruction);
+
+        let distance = instruction.from.abs_diff(instruction.to) as u64;
+        self.per_move + self.per_crate * instruction.count as u64 + self.per_stack * distance
+    }
+
+    fn move_within<T: Clone>(&self, stack: &mut Vector<T>, instruction: &Instruction) -> u64 {
+        self.crane.move_within(stack, instruction);
+
+        self.per_move + self.per_crate * instruction.count as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use imbl::vector::Vector;
+
+    // moves (from, to, count) of three stacks of 4996 crates : the first ones split and append the
+    // stacks, the last ones pop and push the crates one at a time, which panics with im 15.1
+    const SPLIT_MOVES: [(usize, usize, usize); 19] = [
+        (0, 1, 318),
+        (2, 1, 4932),
+        (0, 1, 4421),
+        (1, 2, 4085),
+        (1, 2, 6939),
+        (2, 0, 11088),
+        (1, 0, 407),
+        (1, 0, 2360),
+        (0, 1, 13786),
+        (1, 0, 2187),
+        (1, 2, 6788),
+        (2, 1, 1941),
+        (0, 2, 334),
+        (1, 2, 2301),
+        (2, 0, 7246),
+        (0, 1, 5699),
+        (2, 1, 236),
+        (1, 0, 1),
+        (0, 1, 903),
+    ];
+    const POP_MOVES: [(usize, usize, usize); 2] = [(0, 1, 65), (1, 2, 66)];
+
+    #[test]
+    fn split_and_append_keep_valid_stacks() {
+        let mut stacks: Vec<Vector<u32>> = (0..3).map(|_| (0..4996).collect()).collect();
+        let mut expected: Vec<Vec<u32>> = (0..3).map(|_| (0..4996).collect()).collect();
+
+        for (from, to, count) in SPLIT_MOVES {
+            let height = stacks[from].len();
+            let moved = stacks[from].split_off(height - count);
+            stacks[to].append(moved);
+            let moved = expected[from].split_off(height - count);
+            expected[to].extend(moved);
+        }
+        for (from, to, count) in POP_MOVES {
+            for _ in 0..count {
+                let crate_id = stacks[from].pop_back().unwrap();
+                stacks[to].push_back(crate_id);
+                let crate_id = expected[from].pop().unwrap();
+                expected[to].push(crate_id);
+            }
+        }
+
+        for (stack, expected) in stacks.iter().zip(expected) {
+            assert_eq!(stack.iter().copied().collect::<Vec<u32>>(), expected);
+        }
+    }
+}
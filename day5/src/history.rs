@@ -0,0 +1,186 @@
+// history of an execution : every intermediate state is kept, which is cheap
+// since states are persistent vectors sharing most of their nodes
+
+use crate::crane::Crane;
+use crate::render::render_state;
+use crate::{exec_instruction, ExecError, Instruction, Stacks, State};
+use std::io::{self, BufRead, Write};
+
+pub struct History<T: Clone> {
+    instructions: Vec<Instruction>,
+    // states[k] is the state after k steps, states[0] the initial state
+    states: Vec<Stacks<T>>,
+    // costs[k] is the cost of step k + 1
+    costs: Vec<u64>,
+}
+
+impl<T: Clone> History<T> {
+    // stops at the first instruction that cannot be executed
+    pub fn record<C: Crane>(crane: &C, instructions: &[Instruction], initial: &Stacks<T>) -> Result<History<T>, ExecError> {
+        let mut states = vec![initial.clone()];
+        let mut costs = vec![];
+
+        for instruction in instructions {
+            let (state, cost) = exec_instruction(crane, instruction, states.last().unwrap())?;
+            states.push(state);
+            costs.push(cost);
+        }
+
+        Ok(History { instructions: instructions.to_vec(), states, costs })
+    }
+
+    // number of executed instructions
+    pub fn steps(&self) -> usize {
+        self.instructions.len()
+    }
+
+    // state after the first k steps
+    pub fn state(&self, k: usize) -> &Stacks<T> {
+        &self.states[k]
+    }
+
+    // instruction executed at step k (starting from 1)
+    pub fn instruction(&self, k: usize) -> &Instruction {
+        &self.instructions[k - 1]
+    }
+
+    pub fn total_cost(&self) -> u64 {
+        self.costs.iter().sum()
+    }
+
+    // number of instructions taking from or putting on each stack
+    pub fn touch_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.states[0].len()];
+        for instruction in &self.instructions {
+            counts[instruction.from] += 1;
+            if instruction.to != instruction.from {
+                counts[instruction.to] += 1;
+            }
+        }
+        counts
+    }
+}
+
+impl<T: Clone + PartialEq> History<T> {
+    // steps (starting from 1) at which elem changed of stack or of height
+    pub fn moves_of(&self, elem: &T) -> Vec<usize> {
+        let position = |state: &Stacks<T>| {
+            state
+                .iter()
+                .enumerate()
+                .find_map(|(stack, crates)| crates.index_of(elem).map(|level| (stack, level)))
+        };
+
+        self.states
+            .windows(2)
+            .enumerate()
+            .filter(|(_, w)| position(&w[0]) != position(&w[1]))
+            .map(|(k, _)| k + 1)
+            .collect()
+    }
+}
+
+// number the crates of a state, stack by stack from the bottom, so that crates with the same label can be followed
+pub fn crate_ids<T: Clone>(state: &Stacks<T>) -> Stacks<usize> {
+    let mut next = 0;
+    state
+        .iter()
+        .map(|stack| {
+            stack
+                .iter()
+                .map(|_| {
+                    next += 1;
+                    next - 1
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn print_step(history: &History<String>, k: usize) {
+    if k == 0 {
+        println!("step 0/{} : initial state", history.steps());
+    } else {
+        println!("step {}/{} : {}", k, history.steps(), history.instruction(k));
+    }
+    println!("{}", render_state(history.state(k)));
+}
+
+// scrub through the history from stdin :
+// n (next step), p (previous step), g k (go to step k), q (quit)
+pub fn scrub(history: &History<String>) {
+    let mut k = 0;
+    print_step(history, k);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let command: Vec<&str> = line.split_whitespace().collect();
+        match command.as_slice() {
+            ["n"] if k < history.steps() => k += 1,
+            ["p"] if k > 0 => k -= 1,
+            ["n"] | ["p"] => {
+                println!("no more steps");
+                continue;
+            }
+            ["g", step] => match step.parse::<usize>() {
+                Ok(step) if step <= history.steps() => k = step,
+                _ => {
+                    println!("invalid step : {}", step);
+                    continue;
+                }
+            },
+            ["q"] => break,
+            _ => {
+                println!("commands : n, p, g <step>, q");
+                continue;
+            }
+        }
+        print_step(history, k);
+    }
+}
+
+// cargo run -- history state <k> | crate <stack> <height> | stacks | scrub
+pub fn run_history<C: Crane>(crane: &C, args: &[String], instructions: &[Instruction], initial: &State) {
+    let history = History::record(crane, instructions, initial).unwrap_or_else(|e| panic!("{}", e));
+    let arg = |i: usize| args.get(i).map(|s| s.parse::<usize>().unwrap());
+
+    match args.get(2).map(|s| s.as_str()) {
+        Some("state") => {
+            let k = arg(3).unwrap_or(history.steps());
+            if k > history.steps() {
+                panic!("Invalid number of steps : {} (the input has {} instructions)", k, history.steps());
+            }
+            print_step(&history, k);
+        }
+        Some("crate") => {
+            // crate given by its stack (starting from 1) and its height (starting from 1 at the bottom) in the initial state
+            let (stack, height) = (arg(3).unwrap() - 1, arg(4).unwrap() - 1);
+            let ids = crate_ids(initial);
+            let id = match ids.get(stack).and_then(|s| s.get(height)) {
+                Some(id) => *id,
+                None => panic!("No crate at stack {} height {}", stack + 1, height + 1),
+            };
+            let moves = History::record(crane, instructions, &ids).unwrap_or_else(|e| panic!("{}", e)).moves_of(&id);
+
+            println!("crate [{}] moved {} times with {}", initial[stack][height], moves.len(), crane.name());
+            for k in moves {
+                println!("step {} : {}", k, history.instruction(k));
+            }
+        }
+        Some("stacks") => {
+            for (stack, count) in history.touch_counts().iter().enumerate() {
+                println!("stack {} : touched by {} instructions", stack + 1, count);
+            }
+            println!("total cost : {}", history.total_cost());
+        }
+        Some("scrub") => scrub(&history),
+        _ => panic!("Invalid history command, expected state, crate, stacks or scrub"),
+    }
+}
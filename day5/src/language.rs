@@ -0,0 +1,253 @@
+// crane instruction language
+//
+// one statement per line, blank lines and comments starting with '#' are ignored :
+//     move 3 from 1 to 2       # moves the 3 crates on top of stack 1 to stack 2
+//     move [X] from 1 to 2     # moves crate X with all the crates above it
+//     swap 1 3                 # exchanges two stacks
+//     reverse 2                # turns a stack upside down
+//     rotate                   # moves every stack one place to the right, the last one becoming the first
+//     rotate -2                # moves every stack two places to the left
+// moves are done by the crane, the other statements rearrange the stacks themselves and cost nothing
+// errors are reported with the line and column where parsing failed
+
+use crate::crane::Crane;
+use crate::render::render_state;
+use crate::{exec_instruction_mut, get_top_line, split_input, Elem, ExecError, ExecErrorKind, Instruction, State};
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Op {
+    Move { count: usize, from: usize, to: usize },
+    MoveCrate { label: Elem, from: usize, to: usize },
+    Swap(usize, usize),
+    Reverse(usize),
+    Rotate(i64),
+}
+
+// stacks are numbered from 0, as in instructions
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Statement {
+    // line of the statement in the input file
+    pub line: usize,
+    pub op: Op,
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.op {
+            Op::Move { count, from, to } => write!(f, "move {} from {} to {}", count, from + 1, to + 1),
+            Op::MoveCrate { label, from, to } => write!(f, "move [{}] from {} to {}", label, from + 1, to + 1),
+            Op::Swap(a, b) => write!(f, "swap {} {}", a + 1, b + 1),
+            Op::Reverse(stack) => write!(f, "reverse {}", stack + 1),
+            Op::Rotate(shift) => write!(f, "rotate {}", shift),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    // position of the error, both starting at 1
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {} : {}", self.line, self.column, self.message)
+    }
+}
+
+// cursor over the words of a line
+struct LineParser {
+    line: usize,
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl LineParser {
+    fn new(line: usize, text: &str) -> LineParser {
+        LineParser { line, chars: text.chars().collect(), position: 0 }
+    }
+
+    fn error<T>(&self, column: usize, message: String) -> Result<T, ParseError> {
+        Err(ParseError { line: self.line, column: column + 1, message })
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.chars.get(self.position).is_some_and(| c | c.is_whitespace()) {
+            self.position += 1;
+        }
+    }
+
+    // nothing left but spaces and a comment
+    fn at_end(&mut self) -> bool {
+        self.skip_spaces();
+        matches!(self.chars.get(self.position), None | Some('#'))
+    }
+
+    // next word and its column, a word ends with a space or a comment
+    fn word(&mut self, expected: &str) -> Result<(String, usize), ParseError> {
+        if self.at_end() {
+            return self.error(self.position, format!("expected {}, found end of line", expected));
+        }
+        let column = self.position;
+        while self.chars.get(self.position).is_some_and(| c | !c.is_whitespace() && *c != '#') {
+            self.position += 1;
+        }
+        Ok((self.chars[column..self.position].iter().collect(), column))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let (word, column) = self.word(&format!("'{}'", keyword))?;
+        if word != keyword {
+            return self.error(column, format!("expected '{}', found '{}'", keyword, word));
+        }
+        Ok(())
+    }
+
+    fn number<T: FromStr>(&mut self, expected: &str) -> Result<(T, usize), ParseError> {
+        let (word, column) = self.word(expected)?;
+        match word.parse::<T>() {
+            Ok(value) => Ok((value, column)),
+            Err(_) => self.error(column, format!("expected {}, found '{}'", expected, word)),
+        }
+    }
+
+    // stack number, starting from 1
+    fn stack(&mut self) -> Result<usize, ParseError> {
+        let (stack, column) = self.number::<usize>("a stack number")?;
+        if stack == 0 {
+            return self.error(column, "stack numbers start at 1".to_string());
+        }
+        Ok(stack - 1)
+    }
+
+    // move : 'move' (count | '[' label ']') 'from' stack 'to' stack
+    fn move_op(&mut self) -> Result<Op, ParseError> {
+        self.skip_spaces();
+        let label = if self.chars.get(self.position) == Some(&'[') {
+            let (word, column) = self.word("a crate")?;
+            match word.strip_prefix('[').and_then(| w | w.strip_suffix(']')) {
+                Some(label) if !label.is_empty() && !label.contains(['[', ']']) => Some(label.to_string()),
+                _ => return self.error(column, format!("invalid crate '{}'", word)),
+            }
+        } else {
+            None
+        };
+        let count = match label {
+            Some(_) => 0,
+            None => self.number::<usize>("a number of crates")?.0,
+        };
+        self.keyword("from")?;
+        let from = self.stack()?;
+        self.keyword("to")?;
+        let to = self.stack()?;
+
+        Ok(match label {
+            Some(label) => Op::MoveCrate { label, from, to },
+            None => Op::Move { count, from, to },
+        })
+    }
+
+    // statement, None for an empty line or a comment
+    fn statement(&mut self) -> Result<Option<Statement>, ParseError> {
+        if self.at_end() {
+            return Ok(None);
+        }
+
+        let (word, column) = self.word("a statement")?;
+        let op = match word.as_str() {
+            "move" => self.move_op()?,
+            "swap" => Op::Swap(self.stack()?, self.stack()?),
+            "reverse" => Op::Reverse(self.stack()?),
+            "rotate" if self.at_end() => Op::Rotate(1),
+            "rotate" => Op::Rotate(self.number::<i64>("a shift")?.0),
+            _ => return self.error(column, format!("unknown statement '{}'", word)),
+        };
+        if !self.at_end() {
+            let (word, column) = self.word("end of line")?;
+            return self.error(column, format!("unexpected '{}' after the statement", word));
+        }
+
+        Ok(Some(Statement { line: self.line, op }))
+    }
+}
+
+// parse the statements of a program, first_line is the line number of the program in the input file
+pub fn parse_program(program: &str, first_line: usize) -> Result<Vec<Statement>, ParseError> {
+    program
+        .lines()
+        .enumerate()
+        .filter_map(| (i, l) | LineParser::new(first_line + i, l).statement().transpose())
+        .collect()
+}
+
+// execute a statement in place with the given crane, returns the cost of the move
+pub fn exec_statement<C: Crane>(crane: &C, statement: &Statement, state: &mut State) -> Result<u64, ExecError> {
+    let error = |kind| Err(ExecError { line: statement.line, statement: statement.to_string(), kind });
+    let stacks = match &statement.op {
+        Op::Move { from, to, .. } | Op::MoveCrate { from, to, .. } | Op::Swap(from, to) => vec![*from, *to],
+        Op::Reverse(stack) => vec![*stack],
+        Op::Rotate(_) => vec![],
+    };
+    if let Some(stack) = stacks.iter().find(| s | **s >= state.len()) {
+        return error(ExecErrorKind::UnknownStack(stack + 1));
+    }
+
+    match &statement.op {
+        Op::Move { count, from, to } => {
+            let instruction = Instruction { count: *count, from: *from, to: *to, line: statement.line };
+            exec_instruction_mut(crane, &instruction, state)
+        }
+        Op::MoveCrate { label, from, to } => {
+            // the crate is taken with all the crates above it
+            let count = match state[*from].iter().rev().position(| e | e == label) {
+                Some(depth) => depth + 1,
+                None => return error(ExecErrorKind::MissingCrate { label: label.clone(), stack: from + 1 }),
+            };
+            let instruction = Instruction { count, from: *from, to: *to, line: statement.line };
+            exec_instruction_mut(crane, &instruction, state)
+        }
+        Op::Swap(a, b) => {
+            state.swap(*a, *b);
+            Ok(0)
+        }
+        Op::Reverse(stack) => {
+            state[*stack] = state[*stack].iter().rev().cloned().collect();
+            Ok(0)
+        }
+        Op::Rotate(shift) => {
+            let n = state.len() as i64;
+            if n > 0 {
+                let shift = shift.rem_euclid(n) as usize;
+                let (first, last) = state.clone().split_at(state.len() - shift);
+                *state = last + first;
+            }
+            Ok(0)
+        }
+    }
+}
+
+// execute all the statements, returns the final state and the total cost
+pub fn exec_program<C: Crane>(crane: &C, program: &[Statement], state: &State) -> Result<(State, u64), ExecError> {
+    let mut state = state.clone();
+    let mut total = 0;
+    for statement in program {
+        total += exec_statement(crane, statement, &mut state)?;
+    }
+    Ok((state, total))
+}
+
+// cargo run -- program <file> : run a file made of a drawing and a program
+pub fn run_program<C: Crane>(crane: &C, path: &str) {
+    let input = fs::read_to_string(path).unwrap();
+    let (state, program, first_line) = split_input(&input);
+    let program = parse_program(&program, first_line).unwrap_or_else(| e | panic!("Invalid program, {}", e));
+    let (result_state, cost) = exec_program(crane, &program, &state).unwrap_or_else(| e | panic!("{}", e));
+
+    println!("{}", render_state(&result_state));
+    println!("{} : {} (cost {})", crane.name(), get_top_line(&result_state), cost);
+}
@@ -1,15 +1,44 @@
-use std::fs::File;
+mod bench;
+mod crane;
+mod history;
+mod language;
+mod planner;
+mod render;
+mod reverse;
+mod stats;
+
+use crane::{BottomMover, Crane, CrateMover9000, CrateMover9001, LimitedCapacity, Metered};
+use history::run_history;
+use language::{parse_program, run_program, Op};
+use planner::run_plan;
+use render::render_state;
+use reverse::run_reverse;
+use stats::run_stats;
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::mem;
 use std::io::Read;
-use regex::Regex;
-use im::vector::Vector;
+use imbl::vector::Vector;
 
-type Elem = char;
-type State = Vector<Vector<Elem>>;
+// crate label, usually a single letter
+type Elem = String;
+type Stacks<T> = Vector<Vector<T>>;
+type State = Stacks<Elem>;
 
+#[derive(Clone, Copy)]
 struct Instruction {
     count: usize,
     from: usize,
-    to: usize
+    to: usize,
+    // line of the instruction in the input file
+    line: usize
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from + 1, self.to + 1)
+    }
 }
 
 type InputPart1 = (Vec<Instruction>, State);
@@ -18,6 +47,36 @@ type InputPart2 = InputPart1;
 
 type Output = String;
 
+// why an instruction cannot be executed on the current state
+enum ExecErrorKind {
+    // stack number (starting from 1) not in the drawing
+    UnknownStack(usize),
+    // the stack (starting from 1) holds less crates than the instruction moves
+    NotEnoughCrates { stack: usize, available: usize, needed: usize },
+    // the crate to move is not in the stack (starting from 1)
+    MissingCrate { label: Elem, stack: usize },
+}
+
+struct ExecError {
+    // line of the instruction in the input file, and its text
+    line: usize,
+    statement: String,
+    kind: ExecErrorKind,
+}
+
+impl fmt::Display for ExecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid instruction at line {} ({}) : ", self.line, self.statement)?;
+        match &self.kind {
+            ExecErrorKind::UnknownStack(stack) => write!(f, "there is no stack {}", stack),
+            ExecErrorKind::NotEnoughCrates { stack, available, needed } => {
+                write!(f, "stack {} holds {} crates, {} needed", stack, available, needed)
+            }
+            ExecErrorKind::MissingCrate { label, stack } => write!(f, "there is no crate [{}] in stack {}", label, stack),
+        }
+    }
+}
+
 // Input string example :
 //             [L] [M]         [M]    
 //         [D] [R] [Z]         [C] [L]
@@ -39,124 +98,231 @@ type Output = String;
 // move 9 from 3 to 1
 // move 11 from 3 to 8
 
+// split a line into its non blank tokens, with the columns (in characters) of their first and last character
+fn tokens(line: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = vec![];
+    let mut current: Option<(usize, String)> = None;
+
+    for (column, c) in line.chars().enumerate() {
+        match (&mut current, c.is_whitespace()) {
+            (Some((_, token)), false) => token.push(c),
+            (None, false) => current = Some((column, c.to_string())),
+            (Some(_), true) => {
+                let (start, token) = current.take().unwrap();
+                tokens.push((start, column - 1, token));
+            }
+            (None, true) => (),
+        }
+    }
+    if let Some((start, token)) = current {
+        let end = start + token.chars().count() - 1;
+        tokens.push((start, end, token));
+    }
+
+    tokens
+}
+
+// find the stack of a crate spanning columns start to end
+// the crate belongs to the footer number under it, or else to the closest one
+fn find_stack(footer: &[(usize, usize, String)], start: usize, end: usize) -> usize {
+    let center = |a: usize, b: usize| (a + b) as i64;
+    footer
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, (s, e, _))| {
+            let overlapping = *s <= end && start <= *e;
+            (!overlapping, (center(*s, *e) - center(start, end)).abs())
+        })
+        .map(|(idx, _)| idx)
+        .unwrap()
+}
+
+// parse the drawing of the stacks
+// the footer line gives the number and position of the stacks, and crates are
+// written as [X] where the label can be several characters long ([AB])
 fn parse_state(state_part: &str) -> State {
-    let state_regex = Regex::new(r"^.(.)...(.)...(.)...(.)...(.)...(.)...(.)...(.)...(.).$").unwrap();
-    let zero_state: State = (0..9).map(|_| Vector::new()).collect();
-
-    let parsed_state = 
-        state_part
-            .lines()
-            .map(| l | {
-                let captures: Vec<Elem> = state_regex.captures(l).unwrap().iter().skip(1).map(| c | c.unwrap().as_str().chars().next().unwrap()).collect();
-                captures
-            }).fold(zero_state, | state, captures | {
-                captures
-                    .iter()
-                    .enumerate()
-                    .filter(| (_idx, elem) | (*elem).is_alphabetic())
-                    .fold(state, | state, (idx, elem) | {
-                        let mut new_value = state[idx].clone();
-                        let mut new_state = state.clone();
-                        new_value.push_back(elem.clone());
-                        new_state.set(idx, new_value);
-                        new_state
-                })
-        });
-
-
-    let actual_state: State = parsed_state.iter().map(| v | v.iter().rev().map(|e| *e).collect::<Vector<Elem>>()).collect();
-
-    actual_state
-}
-
-fn parse_instructions(instruction_part: &str) -> Vec<Instruction> {
-    instruction_part
-        .replace("move ", "")
-        .replace(" from ", ",")
-        .replace(" to ", ",")
-        .lines()
-        .map(|l| {
-            let a = l.split(",").map(| p | p.parse::<usize>().unwrap()).collect::<Vec<usize>>();
-
-            Instruction { count: a[0], from: a[1] - 1, to: a[2] - 1 }
+    let lines: Vec<&str> = state_part.lines().filter(|l| !l.trim().is_empty()).collect();
+    let (footer_line, crate_lines) = match lines.split_last() {
+        Some(split) => split,
+        None => panic!("Missing stack drawing"),
+    };
+
+    let footer = tokens(footer_line);
+    for (idx, (_, _, label)) in footer.iter().enumerate() {
+        if label.parse::<usize>() != Ok(idx + 1) {
+            panic!("Invalid stack number in footer : {} (expected {})", label, idx + 1);
+        }
+    }
+
+    // crates are read from the top, so each stack is reversed at the end
+    let mut stacks: Vec<Vec<Elem>> = vec![vec![]; footer.len()];
+    for line in crate_lines {
+        for (start, end, token) in tokens(line) {
+            // adjacent crates without space between them ([A][B]) form a single token
+            for (offset, label) in crate_labels(&token) {
+                let label_start = start + offset;
+                let label_end = label_start + label.chars().count() + 1;
+                if label_end > end {
+                    panic!("Invalid crate in line : {}", line);
+                }
+                stacks[find_stack(&footer, label_start, label_end)].push(label);
+            }
+        }
+    }
+
+    stacks
+        .into_iter()
+        .map(|stack| stack.into_iter().rev().collect::<Vector<Elem>>())
+        .collect()
+}
+
+// split a token made of crates ([A] or [A][B]) into the labels and the column offset of their '['
+fn crate_labels(token: &str) -> Vec<(usize, Elem)> {
+    let mut labels = vec![];
+    let mut chars = token.chars().enumerate();
+
+    while let Some((offset, c)) = chars.next() {
+        if c != '[' {
+            panic!("Invalid crate : {}", token);
+        }
+        let label: String = chars.by_ref().map(|(_, c)| c).take_while(|c| *c != ']').collect();
+        if label.is_empty() {
+            panic!("Invalid crate : {}", token);
+        }
+        labels.push((offset, label));
+    }
+
+    labels
+}
+
+// the puzzle only uses plain moves, the other statements are run by the program mode
+// first_line is the line number of the instruction part in the input file
+fn parse_instructions(instruction_part: &str, first_line: usize) -> Vec<Instruction> {
+    let program = parse_program(instruction_part, first_line).unwrap_or_else(| e | panic!("Invalid instructions, {}", e));
+
+    program
+        .iter()
+        .map(| statement | match statement.op {
+            Op::Move { count, from, to } => Instruction { count, from, to, line: statement.line },
+            _ => panic!("Invalid instruction at line {} ({}) : only moves are allowed here", statement.line, statement),
         })
         .collect()
 }
 
+// split the input into the drawing, parsed, and the instruction part with its first line number
+fn split_input(input: &str) -> (State, String, usize) {
+    // the drawing and the instructions are separated by the first blank line
+    let lines: Vec<&str> = input.lines().collect();
+    let blank = lines.iter().position(|l| l.trim().is_empty()).unwrap_or(lines.len());
+    let state = parse_state(&lines[..blank].join("\n"));
+
+    (state, lines[blank..].join("\n"), blank + 1)
+}
+
 // parse the input : String -> Input
 fn parse_input_part1(input: &str) -> InputPart1 {
-    let parts: Vec<&str> = input.split("\r\n\r\n").collect();
-    let state = parse_state(parts[0]);
-    let instruction_part = parse_instructions(parts[1]);
+    let (state, instruction_part, first_line) = split_input(input);
 
-    (instruction_part, state)
+    (parse_instructions(&instruction_part, first_line), state)
 }
 
 const PARSE_INPUT_PART2: fn(&str) -> InputPart2 = parse_input_part1;
 
-fn exec_instruction(instruction: &Instruction, state: &State) -> State {
-    let mut new_state = state.clone();
-    let mut from = new_state[instruction.from as usize].clone();
-    let mut to = new_state[instruction.to as usize].clone();
+// check that the instruction can be executed on the state
+fn validate<T: Clone>(instruction: &Instruction, state: &Stacks<T>) -> Result<(), ExecError> {
+    let error = |kind| Err(ExecError { line: instruction.line, statement: instruction.to_string(), kind });
 
-    for _ in 0..instruction.count {
-        let elem = from.pop_back().unwrap();
-        to.push_back(elem);
+    for stack in [instruction.from, instruction.to] {
+        if stack >= state.len() {
+            return error(ExecErrorKind::UnknownStack(stack + 1));
+        }
+    }
+    let available = state[instruction.from].len();
+    if available < instruction.count {
+        return error(ExecErrorKind::NotEnoughCrates { stack: instruction.from + 1, available, needed: instruction.count });
+    }
+
+    Ok(())
+}
+
+// execute an instruction in place with the given crane, returns the cost of the move
+// crates can be anything, e.g. numbers to follow each crate through the moves
+fn exec_instruction_mut<C: Crane, T: Clone>(crane: &C, instruction: &Instruction, state: &mut Stacks<T>) -> Result<u64, ExecError> {
+    validate(instruction, state)?;
+    if instruction.from == instruction.to {
+        return Ok(crane.move_within(&mut state[instruction.from], instruction));
     }
 
-    new_state.set(instruction.from as usize, from);
-    new_state.set(instruction.to as usize, to);
+    let mut from = mem::take(&mut state[instruction.from]);
+    let cost = crane.move_crates(&mut from, &mut state[instruction.to], instruction);
+    state[instruction.from] = from;
 
-    new_state
+    Ok(cost)
 }
 
-fn exec_instructions(instructions: &Vec<Instruction>, state: &State) -> State {
-    instructions.iter().fold(state.clone(), | state, instruction | exec_instruction(instruction, &state))
+// same, but keeps the state unchanged and returns the new one
+// the states share most of their nodes, so this is cheap
+fn exec_instruction<C: Crane, T: Clone>(crane: &C, instruction: &Instruction, state: &Stacks<T>) -> Result<(Stacks<T>, u64), ExecError> {
+    let mut new_state = state.clone();
+    let cost = exec_instruction_mut(crane, instruction, &mut new_state)?;
+    Ok((new_state, cost))
+}
+
+// execute all the instructions in place, returns the final state and the total cost
+// stops at the first instruction that cannot be executed
+fn exec_instructions<C: Crane>(crane: &C, instructions: &[Instruction], state: &State) -> Result<(State, u64), ExecError> {
+    let mut state = state.clone();
+    let mut total = 0;
+    for instruction in instructions {
+        total += exec_instruction_mut(crane, instruction, &mut state)?;
+    }
+    Ok((state, total))
 }
 
+// same, but panics with the error
+fn exec_valid_instructions<C: Crane>(crane: &C, instructions: &[Instruction], state: &State) -> (State, u64) {
+    exec_instructions(crane, instructions, state).unwrap_or_else(| e | panic!("{}", e))
+}
+
+// empty stacks are shown as a space
 fn get_top_line(state: &State) -> String {
     state
     .iter()
-    .map(| a | a.last().unwrap())
+    .map(| a | a.last().map(| e | e.as_str()).unwrap_or(" "))
     .collect::<String>()
 }
 
 fn solve_part1(input: &InputPart1) -> Output {
-    let result_state = exec_instructions(&input.0, &input.1);
+    let (result_state, _) = exec_valid_instructions(&CrateMover9000, &input.0, &input.1);
 
     get_top_line(&result_state)
 }
 
-fn exec_instruction_part2(instruction: &Instruction, state: &State) -> State {
-    let mut new_state = state.clone();
-    let mut from = new_state[instruction.from as usize].clone();
-    let mut to = new_state[instruction.to as usize].clone();
-    let mut temp = Vector::new();
-
-    for _ in 0..instruction.count {
-        let elem = from.pop_back().unwrap();
-        temp.push_back(elem);
-    }
-
-    for _ in 0..instruction.count {
-        let elem = temp.pop_back().unwrap();
-        to.push_back(elem);
-    }
-
-    new_state.set(instruction.from as usize, from);
-    new_state.set(instruction.to as usize, to);
+fn solve_part2(input: &InputPart2) -> Output {
+    let (result_state, _) = exec_valid_instructions(&CrateMover9001, &input.0, &input.1);
 
-    new_state
+    get_top_line(&result_state)
 }
 
-fn exec_instructions_part2(instructions: &Vec<Instruction>, state: &State) -> State {
-    instructions.iter().fold(state.clone(), | state, instruction | exec_instruction_part2(instruction, &state))
+// print the top line and the total cost of a crane model
+fn print_crane<C: Crane>(crane: &C, input: &InputPart1) {
+    let (result_state, cost) = exec_valid_instructions(crane, &input.0, &input.1);
+    println!("{} : {} (cost {})", crane.name(), get_top_line(&result_state), cost);
 }
 
-fn solve_part2(input: &InputPart2) -> Output {
-    let result_state = exec_instructions_part2(&input.0, &input.1);
+// check that every state met while executing the instructions can be rendered and parsed back
+fn check_round_trip<C: Crane>(crane: &C, input: &InputPart1) -> usize {
+    let mut state = input.1.clone();
 
-    get_top_line(&result_state)
+    for (step, instruction) in input.0.iter().enumerate() {
+        let drawing = render_state(&state);
+        if parse_state(&drawing) != state {
+            panic!("Round trip failed with {} after {} steps :\n{}", crane.name(), step, drawing);
+        }
+        state = exec_instruction(crane, instruction, &state).unwrap_or_else(| e | panic!("{}", e)).0;
+    }
+
+    input.0.len()
 }
 
 // print the output : Output -> ()
@@ -174,8 +340,106 @@ fn read_input() -> String {
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() > 1 && args[1] == "bench" {
+        // cargo run --release -- bench [moves] [crates per stack] [max crates per move]
+        let moves = args.get(2).map(| s | s.parse::<usize>().unwrap()).unwrap_or(1_000_000);
+        let crates = args.get(3).map(| s | s.parse::<usize>().unwrap()).unwrap_or(100_000);
+        let max_count = args.get(4).map(| s | s.parse::<usize>().unwrap()).unwrap_or(10_000);
+        bench::run(moves, crates, max_count);
+        return;
+    }
     let raw_input = read_input();
     let input_part1 = parse_input_part1(&raw_input);
+    if args.len() > 1 && args[1] == "render" {
+        // cargo run -- render [steps] : drawing after the first steps, executed with the CrateMover 9000
+        let steps = args.get(2).map(| s | s.parse::<usize>().unwrap()).unwrap_or(0);
+        if steps > input_part1.0.len() {
+            panic!("Invalid number of steps : {} (the input has {} instructions)", steps, input_part1.0.len());
+        }
+        let (state, _) = exec_valid_instructions(&CrateMover9000, &input_part1.0[..steps], &input_part1.1);
+        println!("{}", render_state(&state));
+        return;
+    }
+    if args.len() > 1 && args[1] == "roundtrip" {
+        // the input may omit the padding at the end of its lines
+        let drawing: Vec<&str> = raw_input.lines().take_while(| l | !l.trim().is_empty()).map(| l | l.trim_end()).collect();
+        let rendered = render_state(&input_part1.1);
+        let rendered: Vec<&str> = rendered.lines().map(| l | l.trim_end()).collect();
+        if rendered != drawing {
+            panic!("Rendered drawing differs from the input");
+        }
+        let steps = check_round_trip(&CrateMover9000, &input_part1) + check_round_trip(&CrateMover9001, &input_part1);
+        println!("round trip ok : input drawing and {} intermediate states", steps);
+        return;
+    }
+    if args.len() > 1 && args[1] == "history" {
+        match args.iter().position(| a | a == "--crane").map(| i | args[i + 1].as_str()) {
+            None | Some("9000") => run_history(&CrateMover9000, &args, &input_part1.0, &input_part1.1),
+            Some("9001") => run_history(&CrateMover9001, &args, &input_part1.0, &input_part1.1),
+            Some(crane) => panic!("Invalid crane : {}", crane),
+        }
+        return;
+    }
+    if args.len() > 1 && args[1] == "reverse" {
+        let file = args.get(2).filter(| a | !a.starts_with("--")).map(| a | a.as_str());
+        match args.iter().position(| a | a == "--crane").map(| i | args[i + 1].as_str()) {
+            None | Some("9000") => run_reverse(&CrateMover9000, file, &input_part1),
+            Some("9001") => run_reverse(&CrateMover9001, file, &input_part1),
+            Some(crane) => panic!("Invalid crane : {}", crane),
+        }
+        return;
+    }
+    if args.len() > 1 && args[1] == "program" {
+        // cargo run -- program [file] [--crane 9001]
+        let file = args.get(2).filter(| a | !a.starts_with("--")).map(| a | a.as_str()).unwrap_or("program.txt");
+        match args.iter().position(| a | a == "--crane").map(| i | args[i + 1].as_str()) {
+            None | Some("9000") => run_program(&CrateMover9000, file),
+            Some("9001") => run_program(&CrateMover9001, file),
+            Some(crane) => panic!("Invalid crane : {}", crane),
+        }
+        return;
+    }
+    if args.len() > 1 && args[1] == "plan" {
+        // cargo run -- plan <top line | layout file> [--input file] [--crane 9001] [--max-states n]
+        let target = args.get(2).map(| a | a.as_str()).unwrap_or_else(|| panic!("Missing target"));
+        let initial = match args.iter().position(| a | a == "--input") {
+            Some(i) => split_input(&fs::read_to_string(&args[i + 1]).unwrap()).0,
+            None => input_part1.1.clone(),
+        };
+        let max_states = match args.iter().position(| a | a == "--max-states") {
+            Some(i) => args[i + 1].parse::<usize>().unwrap(),
+            None => 1_000_000,
+        };
+        match args.iter().position(| a | a == "--crane").map(| i | args[i + 1].as_str()) {
+            None | Some("9000") => run_plan(&CrateMover9000, target, &initial, max_states),
+            Some("9001") => run_plan(&CrateMover9001, target, &initial, max_states),
+            Some(crane) => panic!("Invalid crane : {}", crane),
+        }
+        return;
+    }
+    if args.len() > 1 && args[1] == "stats" {
+        // cargo run -- stats [--crane 9001] [--svg file]
+        let svg = args.iter().position(| a | a == "--svg").map(| i | args[i + 1].as_str());
+        match args.iter().position(| a | a == "--crane").map(| i | args[i + 1].as_str()) {
+            None | Some("9000") => run_stats(&CrateMover9000, &input_part1.0, &input_part1.1, svg),
+            Some("9001") => run_stats(&CrateMover9001, &input_part1.0, &input_part1.1, svg),
+            Some(crane) => panic!("Invalid crane : {}", crane),
+        }
+        return;
+    }
+    if args.len() > 1 && args[1] == "cranes" {
+        // cargo run -- cranes [capacity of the limited crane]
+        let capacity = args.get(2).map(| s | s.parse::<usize>().unwrap()).unwrap_or(3);
+        let limited = LimitedCapacity::new(capacity);
+        print_crane(&CrateMover9000, &input_part1);
+        print_crane(&CrateMover9001, &input_part1);
+        print_crane(&limited, &input_part1);
+        print_crane(&BottomMover, &input_part1);
+        print_crane(&Metered { crane: CrateMover9001, per_move: 10, per_crate: 1, per_stack: 2 }, &input_part1);
+        return;
+    }
+
     let input_part2 = PARSE_INPUT_PART2(&raw_input);
     let output_part1 = solve_part1(&input_part1);
     let output_part2 = solve_part2(&input_part2);
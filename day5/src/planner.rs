@@ -0,0 +1,142 @@
+// move planner : A* search of a shortest list of instructions leading to a target
+//
+// the target is either a top line, or a full layout of the stacks
+// both heuristics are admissible and consistent, since a move changes at most two stacks
+
+use crate::crane::Crane;
+use crate::render::render_state;
+use crate::{exec_instruction, split_input, Elem, Instruction, State};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs;
+
+pub enum Target {
+    // crate expected on top of each stack, None for an empty stack
+    TopLine(Vec<Option<Elem>>),
+    Layout(State),
+}
+
+impl Target {
+    // one label per character, a space for an empty stack
+    pub fn top_line(line: &str) -> Target {
+        Target::TopLine(line.chars().map(| c | if c == ' ' { None } else { Some(c.to_string()) }).collect())
+    }
+
+    fn stack_count(&self) -> usize {
+        match self {
+            Target::TopLine(tops) => tops.len(),
+            Target::Layout(layout) => layout.len(),
+        }
+    }
+
+    fn is_reached(&self, state: &State) -> bool {
+        self.heuristic(state) == 0
+    }
+
+    // lower bound of the number of moves left
+    fn heuristic(&self, state: &State) -> usize {
+        match self {
+            // a move changes the top of two stacks
+            Target::TopLine(tops) => {
+                let wrong = state.iter().zip(tops).filter(| (stack, top) | stack.last() != top.as_ref()).count();
+                wrong.div_ceil(2)
+            }
+            // a move takes from one stack and puts on another one : every stack with crates
+            // above the part shared with the target must be taken from, and every stack missing
+            // crates of the target must be put on
+            Target::Layout(layout) => {
+                let (mut takes, mut puts) = (0, 0);
+                for (stack, target) in state.iter().zip(layout.iter()) {
+                    let shared = stack.iter().zip(target.iter()).take_while(| (a, b) | a == b).count();
+                    takes += (shared < stack.len()) as usize;
+                    puts += (shared < target.len()) as usize;
+                }
+                takes.max(puts)
+            }
+        }
+    }
+}
+
+// every move allowed on the state, each stack is distinct from the other one
+fn moves(state: &State) -> Vec<Instruction> {
+    let n = state.len();
+    (0..n)
+        .flat_map(| from | (0..n).filter(move | to | *to != from).map(move | to | (from, to)))
+        .flat_map(| (from, to) | (1..=state[from].len()).map(move | count | Instruction { count, from, to, line: 0 }))
+        .collect()
+}
+
+// shortest list of instructions leading from the initial state to the target, and the number of explored states
+// the search gives up (None) after exploring max_states states
+pub fn plan<C: Crane>(crane: &C, initial: &State, target: &Target, max_states: usize) -> (Option<Vec<Instruction>>, usize) {
+    if target.stack_count() != initial.len() {
+        panic!("Target has {} stacks, the drawing has {}", target.stack_count(), initial.len());
+    }
+
+    // states are numbered in discovery order, with the state and instruction they come from
+    let mut states: Vec<State> = vec![initial.clone()];
+    let mut parents: Vec<Option<(usize, Instruction)>> = vec![None];
+    let mut distances: HashMap<State, usize> = HashMap::from([(initial.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((target.heuristic(initial), 0, 0))]);
+    let mut explored = 0;
+
+    while let Some(Reverse((_, distance, id))) = queue.pop() {
+        // an entry for a state already reached with a shorter path
+        if distances[&states[id]] < distance {
+            continue;
+        }
+        if target.is_reached(&states[id]) {
+            let mut instructions = vec![];
+            let mut current = id;
+            while let Some((parent, instruction)) = parents[current] {
+                instructions.push(instruction);
+                current = parent;
+            }
+            instructions.reverse();
+            // line : position of the instruction in the plan
+            for (k, instruction) in instructions.iter_mut().enumerate() {
+                instruction.line = k + 1;
+            }
+            return (Some(instructions), explored);
+        }
+        if explored == max_states {
+            break;
+        }
+        explored += 1;
+
+        for instruction in moves(&states[id]) {
+            let (next, _) = exec_instruction(crane, &instruction, &states[id]).unwrap_or_else(| e | panic!("{}", e));
+            if distances.get(&next).is_some_and(| d | *d <= distance + 1) {
+                continue;
+            }
+            distances.insert(next.clone(), distance + 1);
+            queue.push(Reverse((distance + 1 + target.heuristic(&next), distance + 1, states.len())));
+            states.push(next);
+            parents.push(Some((id, instruction)));
+        }
+    }
+
+    (None, explored)
+}
+
+// cargo run -- plan <top line | layout file> [--input file] [--crane 9001] [--max-states n]
+// prints a puzzle input : the initial drawing followed by the planned moves
+pub fn run_plan<C: Crane>(crane: &C, target: &str, initial: &State, max_states: usize) {
+    // a file holds the drawing of the target layout, anything else is a top line
+    let target = match fs::read_to_string(target) {
+        Ok(layout) => Target::Layout(split_input(&layout).0),
+        Err(_) => Target::top_line(target),
+    };
+
+    match plan(crane, initial, &target, max_states) {
+        (Some(instructions), explored) => {
+            eprintln!("{} : {} moves found after exploring {} states", crane.name(), instructions.len(), explored);
+            println!("{}", render_state(initial));
+            println!();
+            for instruction in &instructions {
+                println!("{}", instruction);
+            }
+        }
+        (None, explored) => panic!("No plan found after exploring {} states", explored),
+    }
+}
@@ -0,0 +1,92 @@
+// render a state back to the drawing format of the input :
+//     [D]    
+// [N] [C]    
+// [Z] [M] [P]
+//  1   2   3 
+// every column is as wide as the longest label, and lines are padded to the full width
+
+use crate::State;
+
+pub fn render_state(state: &State) -> String {
+    let label_width = state.iter().flat_map(|stack| stack.iter()).map(|e| e.chars().count()).max().unwrap_or(1);
+    let width = label_width + 2;
+    let height = state.iter().map(|stack| stack.len()).max().unwrap_or(0);
+
+    let mut lines: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            state
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(elem) => format!("{:<width$}", format!("[{}]", elem), width = width),
+                    None => " ".repeat(width),
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect();
+
+    // numbers are centered under their column
+    let footer = (1..=state.len())
+        .map(|number| {
+            let number = number.to_string();
+            let left = width.saturating_sub(number.len()) / 2;
+            format!("{:<width$}", " ".repeat(left) + &number, width = width)
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    lines.push(footer);
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render_state;
+    use crate::{parse_state, State};
+
+    // stacks are given bottom first
+    fn state(stacks: &[&[&str]]) -> State {
+        stacks.iter().map(|stack| stack.iter().map(|label| label.to_string()).collect()).collect()
+    }
+
+    fn assert_round_trip(state: &State) {
+        let drawing = render_state(state);
+        assert_eq!(parse_state(&drawing), *state, "drawing :\n{}", drawing);
+    }
+
+    #[test]
+    fn single_letters() {
+        assert_round_trip(&state(&[&["Z", "N"], &["M", "C", "D"], &["P"]]));
+    }
+
+    #[test]
+    fn multi_character_labels() {
+        assert_round_trip(&state(&[&["AB", "C"], &["DEF"], &["G", "HIJK", "L"]]));
+    }
+
+    #[test]
+    fn empty_stacks() {
+        assert_round_trip(&state(&[&[], &["A", "B"], &[], &["C"], &[]]));
+        assert_round_trip(&state(&[&[], &[], &[]]));
+    }
+
+    #[test]
+    fn ragged_heights() {
+        assert_round_trip(&state(&[&["A"], &["B", "C", "D", "E", "F", "G"], &["H", "I"], &["J", "K", "L", "M"]]));
+    }
+
+    #[test]
+    fn more_than_nine_stacks() {
+        let labels = ["A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L"];
+        let stacks: Vec<&[&str]> = (0..12).map(|i| &labels[..i % 4]).collect();
+        assert_round_trip(&state(&stacks));
+        assert_round_trip(&state(&[&["AB"], &[], &["C"], &["D"], &["E"], &["F"], &["G"], &["H"], &["I"], &["J"], &["KLM"]]));
+    }
+
+    #[test]
+    fn drawing_matches_the_input_format() {
+        let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+        assert_eq!(render_state(&parse_state(drawing)), drawing);
+    }
+}
@@ -0,0 +1,68 @@
+// reverse execution : recover the initial layout from the final one and the instructions
+//
+// both CrateMover models undo a move with the opposite move : the 9000 reverses the crates
+// again, and the 9001 keeps their order. Each undone move needs the moved crates on the
+// destination stack, so a successful reverse execution also proves every instruction was legal
+
+use crate::crane::{Crane, CrateMover9000, CrateMover9001};
+use crate::render::render_state;
+use crate::{exec_instruction_mut, exec_instructions, parse_input_part1, ExecError, Instruction, Stacks, State};
+use std::fs;
+
+pub trait Reversible: Crane {
+    // instruction undoing `instruction` with the same crane
+    fn undo(&self, instruction: &Instruction) -> Instruction;
+}
+
+impl Reversible for CrateMover9000 {
+    fn undo(&self, instruction: &Instruction) -> Instruction {
+        Instruction { from: instruction.to, to: instruction.from, ..*instruction }
+    }
+}
+
+impl Reversible for CrateMover9001 {
+    fn undo(&self, instruction: &Instruction) -> Instruction {
+        Instruction { from: instruction.to, to: instruction.from, ..*instruction }
+    }
+}
+
+// state before the instructions, given the state after them
+// errors name the instruction that cannot have been executed
+pub fn exec_reverse<C: Reversible, T: Clone>(crane: &C, instructions: &[Instruction], final_state: &Stacks<T>) -> Result<Stacks<T>, ExecError> {
+    let mut state = final_state.clone();
+    for instruction in instructions.iter().rev() {
+        exec_instruction_mut(crane, &crane.undo(instruction), &mut state)
+            .map_err(|e| ExecError { statement: instruction.to_string(), ..e })?;
+    }
+    Ok(state)
+}
+
+// check that a move log leads from the before drawing to the after drawing
+pub fn matches<C: Reversible>(crane: &C, before: &State, instructions: &[Instruction], after: &State) -> Result<bool, ExecError> {
+    Ok(exec_reverse(crane, instructions, after)? == *before)
+}
+
+// cargo run -- reverse [file]
+// with a file made of a final drawing and instructions, print the puzzle input leading to it
+// without file, check that reversing the execution of input.txt gives back its drawing
+pub fn run_reverse<C: Reversible>(crane: &C, file: Option<&str>, input: &(Vec<Instruction>, State)) {
+    match file {
+        Some(path) => {
+            let (instructions, final_state) = parse_input_part1(&fs::read_to_string(path).unwrap());
+            let initial = exec_reverse(crane, &instructions, &final_state).unwrap_or_else(|e| panic!("{}", e));
+            println!("{}", render_state(&initial));
+            println!();
+            for instruction in &instructions {
+                println!("{}", instruction);
+            }
+        }
+        None => {
+            let (final_state, _) = exec_instructions(crane, &input.0, &input.1).unwrap_or_else(|e| panic!("{}", e));
+            match matches(crane, &input.1, &input.0, &final_state) {
+                Ok(true) => println!("{} : initial layout recovered from the final one", crane.name()),
+                Ok(false) => panic!("{} : reverse execution does not give back the initial layout", crane.name()),
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+}
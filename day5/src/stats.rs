@@ -0,0 +1,187 @@
+// movement statistics of a run, computed from the sequence of states of its history
+//
+// crates are followed by numbering them (see crate_ids), so that crates with the
+// same label are told apart
+
+use crate::crane::Crane;
+use crate::history::{crate_ids, History};
+use crate::{Instruction, Stacks, State};
+use std::fs;
+
+// shades of the text heat map, from empty to the highest stack
+const SHADES: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+// number of columns of the heat maps, each one covering several steps if needed
+const WIDTH: usize = 100;
+
+struct Stats {
+    // label and initial position (stack, height) of every crate, both starting from 0
+    crates: Vec<(String, usize, usize)>,
+    // number of moves and number of stacks travelled by every crate
+    moves: Vec<usize>,
+    distances: Vec<usize>,
+    // pair_moves[from][to] is the number of instructions from a stack to another
+    pair_moves: Vec<Vec<usize>>,
+    max_heights: Vec<usize>,
+    // heights[k][stack] is the height of the stack after k steps
+    heights: Vec<Vec<usize>>,
+}
+
+// position (stack, height) of every crate of a numbered state
+fn positions(state: &Stacks<usize>, crates: usize) -> Vec<(usize, usize)> {
+    let mut positions = vec![(0, 0); crates];
+    for (stack, crates) in state.iter().enumerate() {
+        for (height, id) in crates.iter().enumerate() {
+            positions[*id] = (stack, height);
+        }
+    }
+    positions
+}
+
+fn analyse<C: Crane>(crane: &C, instructions: &[Instruction], initial: &State) -> Stats {
+    let ids = crate_ids(initial);
+    let history = History::record(crane, instructions, &ids).unwrap_or_else(| e | panic!("{}", e));
+    let stacks = initial.len();
+
+    let crates: Vec<(String, usize, usize)> = initial
+        .iter()
+        .enumerate()
+        .flat_map(| (stack, crates) | crates.iter().enumerate().map(move | (height, label) | (label.clone(), stack, height)))
+        .collect();
+
+    // a crate moved during a step if its stack or its height changed
+    let mut moves = vec![0; crates.len()];
+    let mut distances = vec![0; crates.len()];
+    let mut before = positions(history.state(0), crates.len());
+    for k in 1..=history.steps() {
+        let after = positions(history.state(k), crates.len());
+        for (id, ((from, from_height), (to, to_height))) in before.iter().zip(after.iter()).enumerate() {
+            if (from, from_height) != (to, to_height) {
+                moves[id] += 1;
+                distances[id] += from.abs_diff(*to);
+            }
+        }
+        before = after;
+    }
+
+    let mut pair_moves = vec![vec![0; stacks]; stacks];
+    for instruction in instructions {
+        pair_moves[instruction.from][instruction.to] += 1;
+    }
+
+    let heights: Vec<Vec<usize>> = (0..=history.steps())
+        .map(| k | history.state(k).iter().map(| stack | stack.len()).collect())
+        .collect();
+    let max_heights = (0..stacks).map(| stack | heights.iter().map(| h | h[stack]).max().unwrap()).collect();
+
+    Stats { crates, moves, distances, pair_moves, max_heights, heights }
+}
+
+// stack heights sampled on at most WIDTH columns, each column keeps the highest height of its steps
+fn sample(heights: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let steps_per_column = heights.len().div_ceil(WIDTH);
+    heights
+        .chunks(steps_per_column)
+        .map(| chunk | (0..chunk[0].len()).map(| stack | chunk.iter().map(| h | h[stack]).max().unwrap()).collect())
+        .collect()
+}
+
+// one line per stack, one column per group of steps, darker for higher stacks
+fn heat_map_text(heights: &[Vec<usize>], max: usize) -> String {
+    let columns = sample(heights);
+    let stacks = heights[0].len();
+    (0..stacks)
+        .map(| stack | {
+            let line: String = columns
+                .iter()
+                .map(| column | SHADES[column[stack] * (SHADES.len() - 1) / max.max(1)])
+                .collect();
+            format!("{:>3} |{}|", stack + 1, line)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// same heat map as a grid of squares, from white (empty) to red (highest)
+fn heat_map_svg(heights: &[Vec<usize>], max: usize) -> String {
+    const CELL: usize = 8;
+    let columns = sample(heights);
+    let stacks = heights[0].len();
+
+    let mut svg = vec![format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\">",
+        columns.len() * CELL,
+        stacks * CELL
+    )];
+    for (x, column) in columns.iter().enumerate() {
+        for (y, height) in column.iter().enumerate() {
+            let shade = 255 - height * 255 / max.max(1);
+            svg.push(format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"rgb(255,{},{})\"><title>stack {} : {} crates</title></rect>",
+                x * CELL,
+                y * CELL,
+                CELL,
+                CELL,
+                shade,
+                shade,
+                y + 1,
+                height
+            ));
+        }
+    }
+    svg.push("</svg>".to_string());
+    svg.join("\n")
+}
+
+// cargo run -- stats [--crane 9001] [--svg file]
+pub fn run_stats<C: Crane>(crane: &C, instructions: &[Instruction], initial: &State, svg: Option<&str>) {
+    let stats = analyse(crane, instructions, initial);
+    println!("{} : {} crates, {} steps", crane.name(), stats.crates.len(), instructions.len());
+
+    println!();
+    println!("crates by distance travelled (in stacks) :");
+    let mut order: Vec<usize> = (0..stats.crates.len()).collect();
+    order.sort_by_key(| id | (std::cmp::Reverse(stats.distances[*id]), *id));
+    for id in order.iter().filter(| id | stats.moves[**id] > 0) {
+        let (label, stack, height) = &stats.crates[*id];
+        println!(
+            "  [{}] from stack {} height {} : {} stacks in {} moves",
+            label,
+            stack + 1,
+            height + 1,
+            stats.distances[*id],
+            stats.moves[*id]
+        );
+    }
+
+    let never_moved: Vec<String> = stats
+        .crates
+        .iter()
+        .enumerate()
+        .filter(| (id, _) | stats.moves[*id] == 0)
+        .map(| (_, (label, stack, height)) | format!("[{}] (stack {} height {})", label, stack + 1, height + 1))
+        .collect();
+    println!("never moved : {}", if never_moved.is_empty() { "none".to_string() } else { never_moved.join(", ") });
+
+    println!();
+    println!("moves per stack pair (from \\ to) :");
+    let stacks = stats.pair_moves.len();
+    println!("    {}", (1..=stacks).map(| to | format!("{:>4}", to)).collect::<String>());
+    for (from, row) in stats.pair_moves.iter().enumerate() {
+        println!("{:>3} {}", from + 1, row.iter().map(| count | format!("{:>4}", count)).collect::<String>());
+    }
+
+    println!();
+    for (stack, height) in stats.max_heights.iter().enumerate() {
+        println!("stack {} : max height {}", stack + 1, height);
+    }
+
+    let max = stats.max_heights.iter().copied().max().unwrap_or(0);
+    println!();
+    println!("stack heights over time (darkest : {} crates) :", max);
+    println!("{}", heat_map_text(&stats.heights, max));
+
+    if let Some(path) = svg {
+        fs::write(path, heat_map_svg(&stats.heights, max)).unwrap();
+        println!("heat map written to {}", path);
+    }
+}